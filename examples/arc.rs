@@ -1,6 +1,6 @@
 use nannou::prelude::*;
 
-use nannou_rough::core::Options;
+use nannou_rough::core::{FillStyle, Options};
 use nannou_rough::generator::RoughGenerator;
 
 fn main() {
@@ -11,9 +11,15 @@ fn view(app: &App, frame: Frame) {
     let draw = app.draw();
     if frame.nth() == 0 {
         draw.background().color(BEIGE);
-        let mut options = Options::new();
-        options.set_fill().set_fill_style("Hachure");
-        RoughGenerator::arc(0.0, 0.0, 800.0, 500.0, 0.0, f32::PI() * 1.8, true, options)
+        let options = Options::builder()
+            .fill(true)
+            .fill_style(FillStyle::Hachure)
+            .build()
+            .unwrap();
+        let mut generator = RoughGenerator::new(options);
+        generator
+            .arc(0.0, 0.0, 800.0, 500.0, 0.0, f32::PI() * 1.8, true, None)
+            .unwrap()
             .draw(&draw);
     }
     draw.to_frame(app, &frame).unwrap();
@@ -0,0 +1,31 @@
+use nannou::prelude::*;
+
+use nannou_rough::core::Options;
+use nannou_rough::generator::RoughGenerator;
+
+fn main() {
+    nannou::sketch(view).size(1000, 1000).run();
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    if frame.nth() == 0 {
+        draw.background().color(BEIGE);
+        let mut generator = RoughGenerator::new(Options::new());
+        generator
+            .quadratic_bezier(pt2(-400.0, 200.0), pt2(0.0, 450.0), pt2(400.0, 200.0), None)
+            .unwrap()
+            .draw(&draw);
+        generator
+            .cubic_bezier(
+                pt2(-400.0, -300.0),
+                pt2(-200.0, 100.0),
+                pt2(200.0, -500.0),
+                pt2(400.0, -100.0),
+                None,
+            )
+            .unwrap()
+            .draw(&draw);
+    }
+    draw.to_frame(app, &frame).unwrap();
+}
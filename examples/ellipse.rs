@@ -1,6 +1,6 @@
 use nannou::prelude::*;
 
-use nannou_rough::core::Options;
+use nannou_rough::core::{FillStyle, Options};
 use nannou_rough::generator::RoughGenerator;
 
 fn main() {
@@ -11,14 +11,18 @@ fn view(app: &App, frame: Frame) {
     let draw = app.draw();
     if frame.nth() % 10 == 0 {
         draw.background().color(BEIGE);
-        let mut options = Options::new();
-        options.set_fill().set_fill_style("Hachure");
+        let options = Options::builder()
+            .fill(true)
+            .fill_style(FillStyle::Hachure)
+            .build()
+            .unwrap();
+        let mut generator = RoughGenerator::new(options);
         let d = 250.0;
         let r = 400.0;
-        RoughGenerator::ellipse(-d, d, r, r, options).draw(&draw);
-        RoughGenerator::ellipse(-d, -d, r, r, options).draw(&draw);
-        RoughGenerator::ellipse(d, -d, r, r, options).draw(&draw);
-        RoughGenerator::ellipse(d, d, r, r, options).draw(&draw);
+        generator.ellipse(-d, d, r, r, None).unwrap().draw(&draw);
+        generator.ellipse(-d, -d, r, r, None).unwrap().draw(&draw);
+        generator.ellipse(d, -d, r, r, None).unwrap().draw(&draw);
+        generator.ellipse(d, d, r, r, None).unwrap().draw(&draw);
     }
     draw.to_frame(app, &frame).unwrap();
 }
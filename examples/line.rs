@@ -11,12 +11,18 @@ fn view(app: &App, frame: Frame) {
     let draw = app.draw();
     if frame.nth() == 0 {
         draw.background().color(BEIGE);
-        let options = Options::new();
+        let mut generator = RoughGenerator::new(Options::new());
         for i in 0..10 {
             let x = map_range(i, 0, 9, -450.0, 450.0);
             let y = map_range(i, 0, 9, -450.0, 450.0);
-            RoughGenerator::line(x, -450.0, x, 450.0, options).draw(&draw);
-            RoughGenerator::line(-450.0, y, 450.0, y, options).draw(&draw);
+            generator
+                .line(x, -450.0, x, 450.0, None)
+                .unwrap()
+                .draw(&draw);
+            generator
+                .line(-450.0, y, 450.0, y, None)
+                .unwrap()
+                .draw(&draw);
         }
     }
     draw.to_frame(app, &frame).unwrap();
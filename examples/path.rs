@@ -0,0 +1,33 @@
+use nannou::prelude::*;
+
+use nannou_rough::core::{FillStyle, Options};
+use nannou_rough::generator::RoughGenerator;
+
+fn main() {
+    nannou::sketch(view).size(1000, 1000).run();
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    if frame.nth() == 0 {
+        draw.background().color(BEIGE);
+        let mut builder = nannou::geom::path::Builder::new().with_svg();
+        builder.move_to([-400.0, -300.0].into());
+        builder.line_to([-400.0, 100.0].into());
+        builder.quadratic_bezier_to([0.0, 500.0].into(), [400.0, 100.0].into());
+        builder.cubic_bezier_to(
+            [300.0, -100.0].into(),
+            [100.0, -400.0].into(),
+            [400.0, -300.0].into(),
+        );
+        builder.close();
+        let options = Options::builder()
+            .fill(true)
+            .fill_style(FillStyle::Hachure)
+            .build()
+            .unwrap();
+        let mut generator = RoughGenerator::new(options);
+        generator.path(&builder.build(), None).unwrap().draw(&draw);
+    }
+    draw.to_frame(app, &frame).unwrap();
+}
@@ -0,0 +1,46 @@
+use nannou::prelude::*;
+
+use nannou_rough::draw::RoughDraw;
+
+fn main() {
+    nannou::sketch(view).size(1000, 1000).run();
+}
+
+fn view(app: &App, frame: Frame) {
+    let draw = app.draw();
+    if frame.nth() == 0 {
+        draw.background().color(BEIGE);
+        draw.rough()
+            .rect()
+            .x_y(-250.0, 250.0)
+            .w_h(300.0, 200.0)
+            .roughness(2.0)
+            .hachure()
+            .color(RED);
+        draw.rough()
+            .ellipse()
+            .x_y(250.0, 250.0)
+            .w_h(300.0, 200.0)
+            .cross_hatch();
+        draw.rough()
+            .line()
+            .start(pt2(-450.0, 0.0))
+            .end(pt2(450.0, 0.0));
+        draw.x_y(-250.0, -250.0)
+            .rotate(PI / 8.0)
+            .rough()
+            .tri()
+            .points([pt2(-150.0, -100.0), pt2(150.0, -100.0), pt2(0.0, 150.0)])
+            .zigzag();
+        draw.x_y(250.0, -250.0)
+            .rough()
+            .polyline()
+            .points((0..8).map(|i| {
+                pt2(
+                    -150.0 + 40.0 * i as f32,
+                    if i % 2 == 0 { -50.0 } else { 50.0 },
+                )
+            }));
+    }
+    draw.to_frame(app, &frame).unwrap();
+}
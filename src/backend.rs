@@ -0,0 +1,406 @@
+use std::slice;
+
+use nannou::lyon::math::{point, Point};
+use nannou::lyon::path::PathEvent;
+use nannou::prelude::*;
+
+use crate::core::{Color, Drawable, Op, OpSetType, Options};
+
+/// Receives the outline of one op set, point by point.
+pub trait PathSink {
+    fn move_to(&mut self, to: Point2);
+    fn line_to(&mut self, to: Point2);
+    fn cubic_to(&mut self, ctrl1: Point2, ctrl2: Point2, to: Point2);
+}
+
+/// Something a `Drawable` can render into. The path sent since the last `stroke` or
+/// `fill` is painted by that call and then discarded.
+///
+/// `options` are the drawable's options, for settings such as caps, joins and
+/// tolerance that a backend may honor.
+pub trait RoughBackend: PathSink {
+    fn stroke(&mut self, color: Color, weight: f32, options: &Options);
+    fn fill(&mut self, color: Color, options: &Options);
+}
+
+/// How an op set is painted.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum Paint {
+    Stroke { color: Color, weight: f32 },
+    Fill { color: Color },
+}
+
+impl Paint {
+    /// Outlines are stroked with `stroke` at `stroke_width`, sketched fills with
+    /// `fill_color` at `fill_weight`, and solid fills are filled with `fill_color`.
+    pub(crate) fn of(ops_type: OpSetType, o: &Options) -> Self {
+        match ops_type {
+            OpSetType::Path => Paint::Stroke {
+                color: o.stroke,
+                weight: o.stroke_width,
+            },
+            OpSetType::FillSketch => Paint::Stroke {
+                color: o.fill_color,
+                weight: o.fill_weight,
+            },
+            OpSetType::FillPath => Paint::Fill {
+                color: o.fill_color,
+            },
+        }
+    }
+}
+
+impl Drawable {
+    /// Sends every op set to `backend`, stroked or filled as `Paint::of` picks.
+    pub fn render<B: RoughBackend + ?Sized>(&self, backend: &mut B) {
+        let o = &self.options;
+        for set in self.sets.iter() {
+            for item in set.ops.iter() {
+                match *item {
+                    Op::Move(to) => backend.move_to(to),
+                    Op::Line(to) => backend.line_to(to),
+                    Op::Cubic(ctrl1, ctrl2, to) => backend.cubic_to(ctrl1, ctrl2, to),
+                }
+            }
+            match Paint::of(set.ops_type, o) {
+                Paint::Stroke { color, weight } => backend.stroke(color, weight, o),
+                Paint::Fill { color } => backend.fill(color, o),
+            }
+        }
+    }
+}
+
+/// Lyon path events for a list of ops, read straight from the slice without building
+/// a path. Every `Move` starts a new open subpath.
+pub struct OpEvents<'a> {
+    ops: slice::Iter<'a, Op>,
+    pending: Option<Op>,
+    first: Point,
+    current: Point,
+    open: bool,
+}
+
+impl<'a> OpEvents<'a> {
+    pub fn new(ops: &'a [Op]) -> Self {
+        return OpEvents {
+            ops: ops.iter(),
+            pending: None,
+            first: point(0.0, 0.0),
+            current: point(0.0, 0.0),
+            open: false,
+        };
+    }
+
+    fn end(&mut self) -> PathEvent {
+        self.open = false;
+        return PathEvent::End {
+            last: self.current,
+            first: self.first,
+            close: false,
+        };
+    }
+}
+
+impl Iterator for OpEvents<'_> {
+    type Item = PathEvent;
+
+    fn next(&mut self) -> Option<PathEvent> {
+        let op = match self.pending.take().or_else(|| self.ops.next().copied()) {
+            Some(op) => op,
+            None if self.open => return Some(self.end()),
+            None => return None,
+        };
+        if let Op::Move(to) = op {
+            if self.open {
+                self.pending = Some(op);
+                return Some(self.end());
+            }
+            self.first = point(to.x, to.y);
+            self.current = self.first;
+            self.open = true;
+            return Some(PathEvent::Begin { at: self.first });
+        }
+        if !self.open {
+            // A subpath without a leading move starts where the last one ended.
+            self.pending = Some(op);
+            self.first = self.current;
+            self.open = true;
+            return Some(PathEvent::Begin { at: self.first });
+        }
+        let from = self.current;
+        self.current = point(op.end().x, op.end().y);
+        return Some(match op {
+            Op::Cubic(ctrl1, ctrl2, _) => PathEvent::Cubic {
+                from,
+                ctrl1: point(ctrl1.x, ctrl1.y),
+                ctrl2: point(ctrl2.x, ctrl2.y),
+                to: self.current,
+            },
+            _ => PathEvent::Line {
+                from,
+                to: self.current,
+            },
+        });
+    }
+}
+
+/// Paints `events` into `draw` with the caps, joins and tolerance of `options`.
+pub(crate) fn draw_events<I>(draw: &Draw, events: I, paint: Paint, options: &Options)
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    match paint {
+        Paint::Stroke { color, weight } => {
+            draw.path()
+                .stroke()
+                .weight(weight)
+                .color(Srgba::from(color))
+                .caps(options.line_cap.into())
+                .join(options.line_join.into())
+                .miter_limit(options.miter_limit)
+                .tolerance(options.tolerance)
+                .events(events);
+        }
+        Paint::Fill { color } => {
+            draw.path()
+                .fill()
+                .color(Srgba::from(color))
+                .tolerance(options.tolerance)
+                .events(events);
+        }
+    }
+}
+
+/// Renders into a nannou `Draw` for code written against `RoughBackend`. The ops of
+/// each op set are gathered in a reused buffer and streamed as lyon events;
+/// `Drawable::draw` skips the buffer and streams its op sets in place.
+pub struct DrawBackend<'a> {
+    draw: &'a Draw,
+    ops: Vec<Op>,
+}
+
+impl<'a> DrawBackend<'a> {
+    pub fn new(draw: &'a Draw) -> Self {
+        return DrawBackend { draw, ops: vec![] };
+    }
+
+    fn paint(&mut self, paint: Paint, options: &Options) {
+        draw_events(self.draw, OpEvents::new(&self.ops), paint, options);
+        self.ops.clear();
+    }
+}
+
+impl PathSink for DrawBackend<'_> {
+    fn move_to(&mut self, to: Point2) {
+        self.ops.push(Op::Move(to));
+    }
+
+    fn line_to(&mut self, to: Point2) {
+        self.ops.push(Op::Line(to));
+    }
+
+    fn cubic_to(&mut self, ctrl1: Point2, ctrl2: Point2, to: Point2) {
+        self.ops.push(Op::Cubic(ctrl1, ctrl2, to));
+    }
+}
+
+impl RoughBackend for DrawBackend<'_> {
+    fn stroke(&mut self, color: Color, weight: f32, options: &Options) {
+        self.paint(Paint::Stroke { color, weight }, options);
+    }
+
+    fn fill(&mut self, color: Color, options: &Options) {
+        self.paint(Paint::Fill { color }, options);
+    }
+}
+
+/// One call received by a `RecordingBackend`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BackendCall {
+    MoveTo(Point2),
+    LineTo(Point2),
+    CubicTo(Point2, Point2, Point2),
+    Stroke { color: Color, weight: f32 },
+    Fill { color: Color },
+}
+
+/// Keeps every call it receives, in order. Useful for checking what a drawable
+/// renders without any output device.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingBackend {
+    pub calls: Vec<BackendCall>,
+}
+
+impl RecordingBackend {
+    pub fn new() -> Self {
+        return RecordingBackend { calls: vec![] };
+    }
+}
+
+impl PathSink for RecordingBackend {
+    fn move_to(&mut self, to: Point2) {
+        self.calls.push(BackendCall::MoveTo(to));
+    }
+
+    fn line_to(&mut self, to: Point2) {
+        self.calls.push(BackendCall::LineTo(to));
+    }
+
+    fn cubic_to(&mut self, ctrl1: Point2, ctrl2: Point2, to: Point2) {
+        self.calls.push(BackendCall::CubicTo(ctrl1, ctrl2, to));
+    }
+}
+
+impl RoughBackend for RecordingBackend {
+    fn stroke(&mut self, color: Color, weight: f32, _options: &Options) {
+        self.calls.push(BackendCall::Stroke { color, weight });
+    }
+
+    fn fill(&mut self, color: Color, _options: &Options) {
+        self.calls.push(BackendCall::Fill { color });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::OpSet;
+
+    fn drawable() -> Drawable {
+        let options = Options {
+            stroke: Color::rgb(1.0, 0.0, 0.0),
+            stroke_width: 3.0,
+            fill_color: Color::rgb(0.0, 0.0, 1.0),
+            fill_weight: 0.5,
+            ..Options::default()
+        };
+        let set = |ops_type| OpSet {
+            ops_type,
+            ops: vec![Op::Move(pt2(0.0, 0.0)), Op::Line(pt2(1.0, 1.0))],
+        };
+        return Drawable::new(
+            "test",
+            options,
+            vec![
+                set(OpSetType::FillPath),
+                set(OpSetType::FillSketch),
+                set(OpSetType::Path),
+            ],
+        );
+    }
+
+    #[test]
+    fn render_paints_each_op_set_with_its_style() {
+        let mut backend = RecordingBackend::new();
+        drawable().render(&mut backend);
+        let path = [
+            BackendCall::MoveTo(pt2(0.0, 0.0)),
+            BackendCall::LineTo(pt2(1.0, 1.0)),
+        ];
+        let expected = [
+            &path[..],
+            &[BackendCall::Fill {
+                color: Color::rgb(0.0, 0.0, 1.0),
+            }],
+            &path[..],
+            &[BackendCall::Stroke {
+                color: Color::rgb(0.0, 0.0, 1.0),
+                weight: 0.5,
+            }],
+            &path[..],
+            &[BackendCall::Stroke {
+                color: Color::rgb(1.0, 0.0, 0.0),
+                weight: 3.0,
+            }],
+        ]
+        .concat();
+        assert_eq!(backend.calls, expected);
+    }
+
+    #[test]
+    fn render_sends_curves() {
+        let mut drawable = drawable();
+        drawable.sets.truncate(1);
+        drawable.sets[0].ops = vec![
+            Op::Move(pt2(0.0, 0.0)),
+            Op::Cubic(pt2(1.0, 0.0), pt2(2.0, 1.0), pt2(3.0, 3.0)),
+        ];
+        let mut backend = RecordingBackend::new();
+        drawable.render(&mut backend);
+        assert_eq!(
+            backend.calls[1],
+            BackendCall::CubicTo(pt2(1.0, 0.0), pt2(2.0, 1.0), pt2(3.0, 3.0))
+        );
+    }
+
+    fn events(ops: &[Op]) -> Vec<PathEvent> {
+        return OpEvents::new(ops).collect();
+    }
+
+    #[test]
+    fn op_events_of_nothing_are_empty() {
+        assert_eq!(events(&[]), vec![]);
+    }
+
+    #[test]
+    fn op_events_start_a_subpath_without_a_move() {
+        let found = events(&[Op::Line(pt2(1.0, 0.0)), Op::Line(pt2(1.0, 1.0))]);
+        assert_eq!(
+            found,
+            vec![
+                PathEvent::Begin {
+                    at: point(0.0, 0.0)
+                },
+                PathEvent::Line {
+                    from: point(0.0, 0.0),
+                    to: point(1.0, 0.0)
+                },
+                PathEvent::Line {
+                    from: point(1.0, 0.0),
+                    to: point(1.0, 1.0)
+                },
+                PathEvent::End {
+                    last: point(1.0, 1.0),
+                    first: point(0.0, 0.0),
+                    close: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn op_events_end_a_subpath_at_every_move() {
+        let found = events(&[
+            Op::Move(pt2(1.0, 1.0)),
+            Op::Move(pt2(2.0, 2.0)),
+            Op::Cubic(pt2(3.0, 2.0), pt2(4.0, 3.0), pt2(5.0, 5.0)),
+        ]);
+        assert_eq!(
+            found,
+            vec![
+                PathEvent::Begin {
+                    at: point(1.0, 1.0)
+                },
+                PathEvent::End {
+                    last: point(1.0, 1.0),
+                    first: point(1.0, 1.0),
+                    close: false
+                },
+                PathEvent::Begin {
+                    at: point(2.0, 2.0)
+                },
+                PathEvent::Cubic {
+                    from: point(2.0, 2.0),
+                    ctrl1: point(3.0, 2.0),
+                    ctrl2: point(4.0, 3.0),
+                    to: point(5.0, 5.0)
+                },
+                PathEvent::End {
+                    last: point(5.0, 5.0),
+                    first: point(2.0, 2.0),
+                    close: false
+                },
+            ]
+        );
+    }
+}
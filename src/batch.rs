@@ -0,0 +1,174 @@
+use nannou::prelude::*;
+
+use crate::backend::{draw_events, OpEvents, Paint};
+use crate::core::{Color, Drawable, LineCap, LineJoin, Op, Options};
+
+/// How a batch is painted. Op sets that share a style end up in the same batch.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Style {
+    Stroke {
+        color: Color,
+        weight: f32,
+        line_cap: LineCap,
+        line_join: LineJoin,
+        miter_limit: f32,
+        tolerance: f32,
+    },
+    Fill {
+        color: Color,
+        tolerance: f32,
+    },
+}
+
+impl Style {
+    fn new(paint: Paint, o: &Options) -> Self {
+        match paint {
+            Paint::Stroke { color, weight } => Style::Stroke {
+                color,
+                weight,
+                line_cap: o.line_cap,
+                line_join: o.line_join,
+                miter_limit: o.miter_limit,
+                tolerance: o.tolerance,
+            },
+            Paint::Fill { color } => Style::Fill {
+                color,
+                tolerance: o.tolerance,
+            },
+        }
+    }
+
+    fn paint(&self) -> Paint {
+        match *self {
+            Style::Stroke { color, weight, .. } => Paint::Stroke { color, weight },
+            Style::Fill { color, .. } => Paint::Fill { color },
+        }
+    }
+}
+
+/// Many drawables merged into one path per style, so a scene of thousands of shapes
+/// costs one `Draw` call per style instead of several per shape.
+///
+/// Fill it once and call `draw` every frame; the merged ops are kept between frames.
+/// Batches are drawn in the order their style first appeared, so shapes of
+/// different styles no longer overlap in the order they were added.
+#[derive(Clone, Default)]
+pub struct DrawableBatch {
+    batches: Vec<Batch>,
+}
+
+/// The ops of one style, painted with the options of the first drawable added to it.
+#[derive(Clone)]
+struct Batch {
+    style: Style,
+    options: Options,
+    ops: Vec<Op>,
+}
+
+impl DrawableBatch {
+    pub fn new() -> Self {
+        return DrawableBatch { batches: vec![] };
+    }
+
+    /// Appends every op set of `drawable` to the batch of its style.
+    pub fn add(&mut self, drawable: &Drawable) -> &mut Self {
+        let o = &drawable.options;
+        for set in drawable.sets.iter() {
+            let style = Style::new(Paint::of(set.ops_type, o), o);
+            match self.batches.iter_mut().find(|b| b.style == style) {
+                Some(batch) => batch.ops.extend_from_slice(&set.ops),
+                None => self.batches.push(Batch {
+                    style,
+                    options: o.clone(),
+                    ops: set.ops.clone(),
+                }),
+            }
+        }
+        self
+    }
+
+    /// The number of distinct styles, which is the number of paths `draw` emits.
+    pub fn len(&self) -> usize {
+        self.batches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.batches.clear();
+    }
+
+    pub fn draw(&self, draw: &Draw) {
+        for batch in self.batches.iter() {
+            let events = OpEvents::new(&batch.ops);
+            draw_events(draw, events, batch.style.paint(), &batch.options);
+        }
+    }
+}
+
+impl<'a> Extend<&'a Drawable> for DrawableBatch {
+    fn extend<I: IntoIterator<Item = &'a Drawable>>(&mut self, drawables: I) {
+        for drawable in drawables {
+            self.add(drawable);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{OpSet, OpSetType};
+
+    fn drawable(stroke: Color, types: &[OpSetType]) -> Drawable {
+        let options = Options {
+            stroke,
+            ..Options::default()
+        };
+        let sets = Vec::from_iter(types.iter().map(|ops_type| OpSet {
+            ops_type: *ops_type,
+            ops: vec![Op::Move(pt2(0.0, 0.0)), Op::Line(pt2(1.0, 1.0))],
+        }));
+        return Drawable::new("test", options, sets);
+    }
+
+    #[test]
+    fn op_sets_with_the_same_style_share_a_batch() {
+        let types = [OpSetType::FillSketch, OpSetType::Path];
+        let mut batch = DrawableBatch::new();
+        assert!(batch.is_empty());
+        batch
+            .add(&drawable(Color::BLACK, &types))
+            .add(&drawable(Color::BLACK, &types));
+        assert_eq!(batch.len(), 2);
+        assert!(batch.batches.iter().all(|b| b.ops.len() == 4));
+    }
+
+    #[test]
+    fn each_style_gets_its_own_batch() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let mut batch = DrawableBatch::new();
+        batch.extend([
+            &drawable(Color::BLACK, &[OpSetType::Path]),
+            &drawable(red, &[OpSetType::Path, OpSetType::FillPath]),
+            &drawable(Color::BLACK, &[OpSetType::FillSketch]),
+        ]);
+        // Black and red outlines, one solid fill, and the sketched fill, which uses
+        // `fill_color` and `fill_weight` and so differs from the black outline.
+        assert_eq!(batch.len(), 4);
+        assert_eq!(
+            batch.batches[0].style,
+            Style::new(
+                Paint::Stroke {
+                    color: Color::BLACK,
+                    weight: Options::default().stroke_width
+                },
+                &Options::default()
+            )
+        );
+        assert!(matches!(batch.batches[2].style, Style::Fill { .. }));
+        batch.clear();
+        assert!(batch.is_empty());
+    }
+}
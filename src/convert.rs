@@ -0,0 +1,124 @@
+use nannou::geom::path::Path;
+use nannou::geom::{Ellipse, Polygon, Quad, Rect, Tri};
+use nannou::prelude::*;
+
+use crate::core::{Drawable, Options};
+use crate::error::{Error, Result};
+use crate::generator;
+
+/// Conversion of nannou geometry into a rough `Drawable`.
+///
+/// nannou describes rectangles and ellipses by their center, while
+/// `generator::rectangle` takes the bottom-left corner, so the
+/// implementations below take care of the offset.
+pub trait IntoRough {
+    fn rough(self, options: Options) -> Result<Drawable>;
+}
+
+impl IntoRough for Rect {
+    fn rough(self, options: Options) -> Result<Drawable> {
+        let (x, y, w, h) = self.xywh();
+        generator::rectangle(x - w / 2.0, y - h / 2.0, w, h, options)
+    }
+}
+
+impl IntoRough for Ellipse {
+    fn rough(self, options: Options) -> Result<Drawable> {
+        let (x, y, w, h) = self.rect.xywh();
+        generator::ellipse(x, y, w, h, options)
+    }
+}
+
+impl IntoRough for Tri<Point2> {
+    fn rough(self, options: Options) -> Result<Drawable> {
+        generator::polygon(self.0.to_vec(), options)
+    }
+}
+
+impl IntoRough for Quad<Point2> {
+    fn rough(self, options: Options) -> Result<Drawable> {
+        generator::polygon(self.0.to_vec(), options)
+    }
+}
+
+impl<I> IntoRough for Polygon<I>
+where
+    I: Iterator<Item = Point2>,
+{
+    fn rough(self, options: Options) -> Result<Drawable> {
+        generator::polygon(self.points.collect(), options)
+    }
+}
+
+impl IntoRough for &Path {
+    fn rough(self, options: Options) -> Result<Drawable> {
+        generator::path(self, options)
+    }
+}
+
+impl TryFrom<Rect> for Drawable {
+    type Error = Error;
+
+    fn try_from(rect: Rect) -> Result<Self> {
+        rect.rough(Options::new())
+    }
+}
+
+impl TryFrom<Ellipse> for Drawable {
+    type Error = Error;
+
+    fn try_from(ellipse: Ellipse) -> Result<Self> {
+        ellipse.rough(Options::new())
+    }
+}
+
+impl TryFrom<Tri<Point2>> for Drawable {
+    type Error = Error;
+
+    fn try_from(tri: Tri<Point2>) -> Result<Self> {
+        tri.rough(Options::new())
+    }
+}
+
+impl TryFrom<Quad<Point2>> for Drawable {
+    type Error = Error;
+
+    fn try_from(quad: Quad<Point2>) -> Result<Self> {
+        quad.rough(Options::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rect_maps_center_to_bottom_left_corner() {
+        let options = Options {
+            seed: 5,
+            ..Options::default()
+        };
+        let rect = Rect::from_x_y_w_h(10.0, 20.0, 40.0, 30.0);
+        let from_rect = rect.rough(options.clone()).unwrap();
+        let expected = generator::rectangle(-10.0, 5.0, 40.0, 30.0, options).unwrap();
+        assert_eq!(from_rect.sets.len(), expected.sets.len());
+        for (found, expected) in from_rect.sets.iter().zip(expected.sets.iter()) {
+            assert_eq!(found.ops, expected.ops);
+        }
+    }
+
+    #[test]
+    fn smooth_rect_outline_spans_the_rect() {
+        let options = Options {
+            roughness: 0.0,
+            ..Options::default()
+        };
+        let rect = Rect::from_x_y_w_h(10.0, 20.0, 40.0, 30.0);
+        let drawable = rect.rough(options).unwrap();
+        let ends = Vec::from_iter(drawable.sets[0].ops.iter().map(|op| op.end()));
+        let min = ends.iter().fold(ends[0], |a, b| a.min(*b));
+        let max = ends.iter().fold(ends[0], |a, b| a.max(*b));
+        assert_eq!(min, pt2(-10.0, 5.0));
+        assert_eq!(max, pt2(30.0, 35.0));
+    }
+}
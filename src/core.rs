@@ -1,20 +1,33 @@
+use std::fmt;
+use std::str::FromStr;
+
+use nannou::lyon::tessellation;
 use nannou::prelude::*;
 
-#[derive(Copy, Clone, Debug)]
+use crate::backend::{draw_events, OpEvents, Paint};
+use crate::error::{Error, Result};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub enum OpType {
     Move,
     BcurveTo,
     LineTo,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub enum OpSetType {
     Path,
     FillPath,
     FillSketch,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
 pub enum FillStyle {
     Solid,
     Zigzag,
@@ -25,11 +38,213 @@ pub enum FillStyle {
     Hachure,
 }
 
+/// How the ends of a stroke are drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum LineCap {
+    Butt,
+    Square,
+    Round,
+}
+
+/// How the corners of a stroke are drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum LineJoin {
+    Miter,
+    MiterClip,
+    Round,
+    Bevel,
+}
+
+impl From<LineCap> for tessellation::LineCap {
+    fn from(cap: LineCap) -> Self {
+        match cap {
+            LineCap::Butt => tessellation::LineCap::Butt,
+            LineCap::Square => tessellation::LineCap::Square,
+            LineCap::Round => tessellation::LineCap::Round,
+        }
+    }
+}
+
+impl From<LineJoin> for tessellation::LineJoin {
+    fn from(join: LineJoin) -> Self {
+        match join {
+            LineJoin::Miter => tessellation::LineJoin::Miter,
+            LineJoin::MiterClip => tessellation::LineJoin::MiterClip,
+            LineJoin::Round => tessellation::LineJoin::Round,
+            LineJoin::Bevel => tessellation::LineJoin::Bevel,
+        }
+    }
+}
+
+/// A non-linear sRGB color with straight alpha, each channel in `0.0..=1.0`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub alpha: f32,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+
+    pub const fn rgb(red: f32, green: f32, blue: f32) -> Self {
+        return Color::rgba(red, green, blue, 1.0);
+    }
+
+    pub const fn rgba(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        return Color {
+            red,
+            green,
+            blue,
+            alpha,
+        };
+    }
+
+    /// `#rrggbb`, as used in SVG attributes. Alpha is left out.
+    pub fn to_hex(&self) -> String {
+        let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        return format!(
+            "#{:02x}{:02x}{:02x}",
+            channel(self.red),
+            channel(self.green),
+            channel(self.blue)
+        );
+    }
+
+    fn is_valid(&self) -> bool {
+        [self.red, self.green, self.blue, self.alpha]
+            .iter()
+            .all(|c| (0.0..=1.0).contains(c))
+    }
+}
+
+impl From<Srgb<u8>> for Color {
+    fn from(color: Srgb<u8>) -> Self {
+        let color = color.into_format::<f32>();
+        return Color::rgb(color.red, color.green, color.blue);
+    }
+}
+
+impl From<Srgba<f32>> for Color {
+    fn from(color: Srgba<f32>) -> Self {
+        return Color::rgba(color.red, color.green, color.blue, color.alpha);
+    }
+}
+
+impl From<Color> for Srgba<f32> {
+    fn from(color: Color) -> Self {
+        return Srgba::new(color.red, color.green, color.blue, color.alpha);
+    }
+}
+
 use FillStyle::*;
 use OpSetType::*;
 use OpType::*;
 
-#[derive(Copy, Clone)]
+/// Lowercases `name` and drops `-` and `_`, so "cross-hatch", "cross_hatch" and
+/// "CrossHatch" all parse to the same variant.
+fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '-' && *c != '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+impl FromStr for FillStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match normalize_name(s).as_str() {
+            "solid" => Ok(Solid),
+            "zigzag" => Ok(Zigzag),
+            "crosshatch" => Ok(CrossHatch),
+            "dots" => Ok(Dots),
+            "dashed" => Ok(Dashed),
+            "zigzagline" => Ok(ZigzagLine),
+            "hachure" => Ok(Hachure),
+            _ => Err(Error::UnknownFillStyle(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for FillStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Solid => "solid",
+            Zigzag => "zigzag",
+            CrossHatch => "cross-hatch",
+            Dots => "dots",
+            Dashed => "dashed",
+            ZigzagLine => "zigzag-line",
+            Hachure => "hachure",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for OpType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match normalize_name(s).as_str() {
+            "move" => Ok(Move),
+            "bcurveto" => Ok(BcurveTo),
+            "lineto" => Ok(LineTo),
+            _ => Err(Error::UnknownOpType(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for OpType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Move => "move",
+            BcurveTo => "bcurveTo",
+            LineTo => "lineTo",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for OpSetType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match normalize_name(s).as_str() {
+            "path" => Ok(Path),
+            "fillpath" => Ok(FillPath),
+            "fillsketch" => Ok(FillSketch),
+            _ => Err(Error::UnknownOpSetType(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for OpSetType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Path => "path",
+            FillPath => "fillPath",
+            FillSketch => "fillSketch",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Rendering options, named like rough.js options.
+///
+/// With the `serde` feature, fields are (de)serialized in camelCase as in rough.js
+/// (`maxRandomnessOffset`, `hachureGap`, ...) and missing fields take their default.
+/// Unlike rough.js, `fill` is a flag rather than a color.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
 pub struct Options {
     pub roughness: f32,
     pub bowing: f32,
@@ -45,6 +260,21 @@ pub struct Options {
     pub curve_step_count: usize,
     pub curve_fitting: f32,
     pub curve_tightness: f32,
+    pub seed: u32,
+    pub stroke_line_dash: Vec<f32>,
+    pub stroke_line_dash_offset: f32,
+    pub fill_line_dash: Vec<f32>,
+    pub fill_line_dash_offset: f32,
+    pub fill_roughness: f32,
+    pub disable_multi_stroke_fill: bool,
+    pub fill_shape_roughness_gain: f32,
+    pub stroke_count: usize,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+    pub miter_limit: f32,
+    pub tolerance: f32,
+    pub stroke: Color,
+    pub fill_color: Color,
 }
 
 impl Options {
@@ -64,37 +294,639 @@ impl Options {
             curve_step_count: 9,
             curve_fitting: 0.95,
             curve_tightness: 0.0,
+            seed: 0,
+            stroke_line_dash: vec![],
+            stroke_line_dash_offset: 0.0,
+            fill_line_dash: vec![],
+            fill_line_dash_offset: 0.0,
+            fill_roughness: -1.0,
+            disable_multi_stroke_fill: false,
+            fill_shape_roughness_gain: 0.8,
+            stroke_count: 2,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            miter_limit: 4.0,
+            tolerance: 0.1,
+            stroke: Color::BLACK,
+            fill_color: Color::BLACK,
         };
     }
 
-    pub fn set_fill_style(&mut self, style: &str) -> &mut Self {
-        match style {
-            "Solid" => self.fill_style = Solid,
-            "Hachure" => self.fill_style = Hachure,
-            _ => unimplemented!(),
+    pub fn builder() -> OptionsBuilder {
+        OptionsBuilder::new()
+    }
+
+    /// Checks that every field is in a range the renderer can handle.
+    pub fn validate(&self) -> Result<()> {
+        fn check(valid: bool, name: &'static str, expected: &'static str) -> Result<()> {
+            if valid {
+                Ok(())
+            } else {
+                Err(Error::InvalidOption { name, expected })
+            }
+        }
+        check(
+            self.roughness.is_finite() && self.roughness >= 0.0,
+            "roughness",
+            "a finite number >= 0",
+        )?;
+        check(self.bowing.is_finite(), "bowing", "a finite number")?;
+        check(
+            self.max_randomness_offset.is_finite() && self.max_randomness_offset >= 0.0,
+            "max_randomness_offset",
+            "a finite number >= 0",
+        )?;
+        check(
+            self.hachure_angle.is_finite(),
+            "hachure_angle",
+            "a finite number",
+        )?;
+        check(
+            self.hachure_gap.is_finite() && self.hachure_gap != 0.0,
+            "hachure_gap",
+            "a positive number, or a negative one to derive it from stroke_width",
+        )?;
+        check(
+            self.stroke_width.is_finite() && self.stroke_width >= 0.0,
+            "stroke_width",
+            "a finite number >= 0",
+        )?;
+        check(
+            self.fill_weight.is_finite() && self.fill_weight >= 0.0,
+            "fill_weight",
+            "a finite number >= 0",
+        )?;
+        check(self.curve_step_count > 0, "curve_step_count", "at least 1")?;
+        check(self.stroke_count > 0, "stroke_count", "at least 1")?;
+        check(self.stroke.is_valid(), "stroke", "channels in 0..=1")?;
+        check(
+            self.fill_color.is_valid(),
+            "fill_color",
+            "channels in 0..=1",
+        )?;
+        check(
+            self.miter_limit >= 1.0 && self.miter_limit.is_finite(),
+            "miter_limit",
+            "a finite number >= 1",
+        )?;
+        check(
+            self.tolerance > 0.0 && self.tolerance.is_finite(),
+            "tolerance",
+            "a finite number > 0",
+        )?;
+        check(
+            (0.0..=1.0).contains(&self.curve_fitting),
+            "curve_fitting",
+            "a number between 0 and 1",
+        )?;
+        check(
+            self.curve_tightness.is_finite(),
+            "curve_tightness",
+            "a finite number",
+        )?;
+        check(
+            valid_dash(&self.stroke_line_dash),
+            "stroke_line_dash",
+            "empty, or finite lengths >= 0 that are not all zero",
+        )?;
+        check(
+            self.stroke_line_dash_offset.is_finite(),
+            "stroke_line_dash_offset",
+            "a finite number",
+        )?;
+        check(
+            valid_dash(&self.fill_line_dash),
+            "fill_line_dash",
+            "empty, or finite lengths >= 0 that are not all zero",
+        )?;
+        check(
+            self.fill_line_dash_offset.is_finite(),
+            "fill_line_dash_offset",
+            "a finite number",
+        )?;
+        check(
+            self.fill_roughness.is_finite(),
+            "fill_roughness",
+            "a number >= 0, or a negative one to use roughness",
+        )?;
+        check(
+            self.fill_shape_roughness_gain.is_finite() && self.fill_shape_roughness_gain >= 0.0,
+            "fill_shape_roughness_gain",
+            "a finite number >= 0",
+        )?;
+        return Ok(());
+    }
+
+    /// How many overlapping strokes each outline is drawn with: `stroke_count`, or one
+    /// when `disable_multi_stroke` is set.
+    pub fn stroke_passes(&self) -> usize {
+        if self.disable_multi_stroke {
+            1
+        } else {
+            self.stroke_count.max(1)
+        }
+    }
+
+    /// The options fillers draw with: `fill_roughness` replaces `roughness` unless it is
+    /// negative, and `disable_multi_stroke_fill` alone decides whether fills use single
+    /// strokes.
+    pub fn fill_options(&self) -> Options {
+        Options {
+            roughness: if self.fill_roughness < 0.0 {
+                self.roughness
+            } else {
+                self.fill_roughness
+            },
+            disable_multi_stroke: self.disable_multi_stroke_fill,
+            ..self.clone()
         }
-        return self;
     }
 
-    pub fn set_fill(&mut self) -> &mut Self {
-        self.fill = true;
-        return self;
+    /// Returns a copy of these options with every field set in `partial` replaced.
+    pub fn merge(&self, partial: &PartialOptions) -> Options {
+        return Options {
+            roughness: partial.roughness.unwrap_or(self.roughness),
+            bowing: partial.bowing.unwrap_or(self.bowing),
+            disable_multi_stroke: partial
+                .disable_multi_stroke
+                .unwrap_or(self.disable_multi_stroke),
+            max_randomness_offset: partial
+                .max_randomness_offset
+                .unwrap_or(self.max_randomness_offset),
+            preserve_vertices: partial.preserve_vertices.unwrap_or(self.preserve_vertices),
+            fill: partial.fill.unwrap_or(self.fill),
+            fill_style: partial.fill_style.unwrap_or(self.fill_style),
+            hachure_angle: partial.hachure_angle.unwrap_or(self.hachure_angle),
+            hachure_gap: partial.hachure_gap.unwrap_or(self.hachure_gap),
+            stroke_width: partial.stroke_width.unwrap_or(self.stroke_width),
+            fill_weight: partial.fill_weight.unwrap_or(self.fill_weight),
+            curve_step_count: partial.curve_step_count.unwrap_or(self.curve_step_count),
+            curve_fitting: partial.curve_fitting.unwrap_or(self.curve_fitting),
+            curve_tightness: partial.curve_tightness.unwrap_or(self.curve_tightness),
+            seed: partial.seed.unwrap_or(self.seed),
+            stroke_line_dash: partial
+                .stroke_line_dash
+                .clone()
+                .unwrap_or_else(|| self.stroke_line_dash.clone()),
+            stroke_line_dash_offset: partial
+                .stroke_line_dash_offset
+                .unwrap_or(self.stroke_line_dash_offset),
+            fill_line_dash: partial
+                .fill_line_dash
+                .clone()
+                .unwrap_or_else(|| self.fill_line_dash.clone()),
+            fill_line_dash_offset: partial
+                .fill_line_dash_offset
+                .unwrap_or(self.fill_line_dash_offset),
+            fill_roughness: partial.fill_roughness.unwrap_or(self.fill_roughness),
+            disable_multi_stroke_fill: partial
+                .disable_multi_stroke_fill
+                .unwrap_or(self.disable_multi_stroke_fill),
+            fill_shape_roughness_gain: partial
+                .fill_shape_roughness_gain
+                .unwrap_or(self.fill_shape_roughness_gain),
+            stroke_count: partial.stroke_count.unwrap_or(self.stroke_count),
+            line_cap: partial.line_cap.unwrap_or(self.line_cap),
+            line_join: partial.line_join.unwrap_or(self.line_join),
+            miter_limit: partial.miter_limit.unwrap_or(self.miter_limit),
+            tolerance: partial.tolerance.unwrap_or(self.tolerance),
+            stroke: partial.stroke.unwrap_or(self.stroke),
+            fill_color: partial.fill_color.unwrap_or(self.fill_color),
+        };
     }
 }
 
-#[derive(Clone, Debug)]
-pub struct Op {
+fn valid_dash(dash: &[f32]) -> bool {
+    dash.iter().all(|d| d.is_finite() && *d >= 0.0)
+        && (dash.is_empty() || dash.iter().sum::<f32>() > 0.0)
+}
+
+/// Fluent, validated construction of `Options`.
+///
+/// ```ignore
+/// let options = Options::builder()
+///     .roughness(2.0)
+///     .fill(true)
+///     .fill_style(FillStyle::Hachure)
+///     .build()?;
+/// ```
+#[derive(Clone)]
+pub struct OptionsBuilder {
+    options: Options,
+}
+
+impl OptionsBuilder {
+    pub fn new() -> Self {
+        OptionsBuilder {
+            options: Options::new(),
+        }
+    }
+
+    pub fn roughness(mut self, roughness: f32) -> Self {
+        self.options.roughness = roughness;
+        self
+    }
+
+    pub fn bowing(mut self, bowing: f32) -> Self {
+        self.options.bowing = bowing;
+        self
+    }
+
+    pub fn disable_multi_stroke(mut self, disable_multi_stroke: bool) -> Self {
+        self.options.disable_multi_stroke = disable_multi_stroke;
+        self
+    }
+
+    pub fn max_randomness_offset(mut self, max_randomness_offset: f32) -> Self {
+        self.options.max_randomness_offset = max_randomness_offset;
+        self
+    }
+
+    pub fn preserve_vertices(mut self, preserve_vertices: bool) -> Self {
+        self.options.preserve_vertices = preserve_vertices;
+        self
+    }
+
+    pub fn fill(mut self, fill: bool) -> Self {
+        self.options.fill = fill;
+        self
+    }
+
+    pub fn fill_style(mut self, fill_style: FillStyle) -> Self {
+        self.options.fill_style = fill_style;
+        self
+    }
+
+    pub fn hachure_angle(mut self, hachure_angle: f32) -> Self {
+        self.options.hachure_angle = hachure_angle;
+        self
+    }
+
+    pub fn hachure_gap(mut self, hachure_gap: f32) -> Self {
+        self.options.hachure_gap = hachure_gap;
+        self
+    }
+
+    pub fn stroke_width(mut self, stroke_width: f32) -> Self {
+        self.options.stroke_width = stroke_width;
+        self
+    }
+
+    pub fn fill_weight(mut self, fill_weight: f32) -> Self {
+        self.options.fill_weight = fill_weight;
+        self
+    }
+
+    pub fn curve_step_count(mut self, curve_step_count: usize) -> Self {
+        self.options.curve_step_count = curve_step_count;
+        self
+    }
+
+    pub fn curve_fitting(mut self, curve_fitting: f32) -> Self {
+        self.options.curve_fitting = curve_fitting;
+        self
+    }
+
+    pub fn curve_tightness(mut self, curve_tightness: f32) -> Self {
+        self.options.curve_tightness = curve_tightness;
+        self
+    }
+
+    pub fn seed(mut self, seed: u32) -> Self {
+        self.options.seed = seed;
+        self
+    }
+
+    pub fn stroke_line_dash(mut self, stroke_line_dash: Vec<f32>) -> Self {
+        self.options.stroke_line_dash = stroke_line_dash;
+        self
+    }
+
+    pub fn stroke_line_dash_offset(mut self, stroke_line_dash_offset: f32) -> Self {
+        self.options.stroke_line_dash_offset = stroke_line_dash_offset;
+        self
+    }
+
+    pub fn fill_line_dash(mut self, fill_line_dash: Vec<f32>) -> Self {
+        self.options.fill_line_dash = fill_line_dash;
+        self
+    }
+
+    pub fn fill_line_dash_offset(mut self, fill_line_dash_offset: f32) -> Self {
+        self.options.fill_line_dash_offset = fill_line_dash_offset;
+        self
+    }
+
+    pub fn fill_roughness(mut self, fill_roughness: f32) -> Self {
+        self.options.fill_roughness = fill_roughness;
+        self
+    }
+
+    pub fn disable_multi_stroke_fill(mut self, disable_multi_stroke_fill: bool) -> Self {
+        self.options.disable_multi_stroke_fill = disable_multi_stroke_fill;
+        self
+    }
+
+    pub fn fill_shape_roughness_gain(mut self, fill_shape_roughness_gain: f32) -> Self {
+        self.options.fill_shape_roughness_gain = fill_shape_roughness_gain;
+        self
+    }
+
+    pub fn stroke_count(mut self, stroke_count: usize) -> Self {
+        self.options.stroke_count = stroke_count;
+        self
+    }
+
+    pub fn line_cap(mut self, line_cap: LineCap) -> Self {
+        self.options.line_cap = line_cap;
+        self
+    }
+
+    pub fn line_join(mut self, line_join: LineJoin) -> Self {
+        self.options.line_join = line_join;
+        self
+    }
+
+    pub fn miter_limit(mut self, miter_limit: f32) -> Self {
+        self.options.miter_limit = miter_limit;
+        self
+    }
+
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.options.tolerance = tolerance;
+        self
+    }
+
+    pub fn stroke(mut self, stroke: Color) -> Self {
+        self.options.stroke = stroke;
+        self
+    }
+
+    pub fn fill_color(mut self, fill_color: Color) -> Self {
+        self.options.fill_color = fill_color;
+        self
+    }
+
+    pub fn build(self) -> Result<Options> {
+        self.options.validate()?;
+        Ok(self.options)
+    }
+}
+
+impl Default for OptionsBuilder {
+    fn default() -> Self {
+        OptionsBuilder::new()
+    }
+}
+
+impl From<Options> for OptionsBuilder {
+    fn from(options: Options) -> Self {
+        OptionsBuilder { options }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options::new()
+    }
+}
+
+impl From<PartialOptions> for Options {
+    fn from(partial: PartialOptions) -> Self {
+        Options::new().merge(&partial)
+    }
+}
+
+/// `Options` with every field optional, used to override a generator's defaults per shape.
+///
+/// Unset fields fall back to whatever they are merged onto, so a config file only needs
+/// to list the fields it changes.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", default))]
+pub struct PartialOptions {
+    pub roughness: Option<f32>,
+    pub bowing: Option<f32>,
+    pub disable_multi_stroke: Option<bool>,
+    pub max_randomness_offset: Option<f32>,
+    pub preserve_vertices: Option<bool>,
+    pub fill: Option<bool>,
+    pub fill_style: Option<FillStyle>,
+    pub hachure_angle: Option<f32>,
+    pub hachure_gap: Option<f32>,
+    pub stroke_width: Option<f32>,
+    pub fill_weight: Option<f32>,
+    pub curve_step_count: Option<usize>,
+    pub curve_fitting: Option<f32>,
+    pub curve_tightness: Option<f32>,
+    pub seed: Option<u32>,
+    pub stroke_line_dash: Option<Vec<f32>>,
+    pub stroke_line_dash_offset: Option<f32>,
+    pub fill_line_dash: Option<Vec<f32>>,
+    pub fill_line_dash_offset: Option<f32>,
+    pub fill_roughness: Option<f32>,
+    pub disable_multi_stroke_fill: Option<bool>,
+    pub fill_shape_roughness_gain: Option<f32>,
+    pub stroke_count: Option<usize>,
+    pub line_cap: Option<LineCap>,
+    pub line_join: Option<LineJoin>,
+    pub miter_limit: Option<f32>,
+    pub tolerance: Option<f32>,
+    pub stroke: Option<Color>,
+    pub fill_color: Option<Color>,
+}
+
+impl PartialOptions {
+    pub fn new() -> Self {
+        PartialOptions::default()
+    }
+
+    /// Layers `other` on top of `self`: fields set in `other` win.
+    pub fn merge(&self, other: &PartialOptions) -> PartialOptions {
+        return PartialOptions {
+            roughness: other.roughness.or(self.roughness),
+            bowing: other.bowing.or(self.bowing),
+            disable_multi_stroke: other.disable_multi_stroke.or(self.disable_multi_stroke),
+            max_randomness_offset: other.max_randomness_offset.or(self.max_randomness_offset),
+            preserve_vertices: other.preserve_vertices.or(self.preserve_vertices),
+            fill: other.fill.or(self.fill),
+            fill_style: other.fill_style.or(self.fill_style),
+            hachure_angle: other.hachure_angle.or(self.hachure_angle),
+            hachure_gap: other.hachure_gap.or(self.hachure_gap),
+            stroke_width: other.stroke_width.or(self.stroke_width),
+            fill_weight: other.fill_weight.or(self.fill_weight),
+            curve_step_count: other.curve_step_count.or(self.curve_step_count),
+            curve_fitting: other.curve_fitting.or(self.curve_fitting),
+            curve_tightness: other.curve_tightness.or(self.curve_tightness),
+            seed: other.seed.or(self.seed),
+            stroke_line_dash: other
+                .stroke_line_dash
+                .clone()
+                .or_else(|| self.stroke_line_dash.clone()),
+            stroke_line_dash_offset: other
+                .stroke_line_dash_offset
+                .or(self.stroke_line_dash_offset),
+            fill_line_dash: other
+                .fill_line_dash
+                .clone()
+                .or_else(|| self.fill_line_dash.clone()),
+            fill_line_dash_offset: other.fill_line_dash_offset.or(self.fill_line_dash_offset),
+            fill_roughness: other.fill_roughness.or(self.fill_roughness),
+            disable_multi_stroke_fill: other
+                .disable_multi_stroke_fill
+                .or(self.disable_multi_stroke_fill),
+            fill_shape_roughness_gain: other
+                .fill_shape_roughness_gain
+                .or(self.fill_shape_roughness_gain),
+            stroke_count: other.stroke_count.or(self.stroke_count),
+            line_cap: other.line_cap.or(self.line_cap),
+            line_join: other.line_join.or(self.line_join),
+            miter_limit: other.miter_limit.or(self.miter_limit),
+            tolerance: other.tolerance.or(self.tolerance),
+            stroke: other.stroke.or(self.stroke),
+            fill_color: other.fill_color.or(self.fill_color),
+        };
+    }
+}
+
+impl From<Options> for PartialOptions {
+    fn from(options: Options) -> Self {
+        return PartialOptions {
+            roughness: Some(options.roughness),
+            bowing: Some(options.bowing),
+            disable_multi_stroke: Some(options.disable_multi_stroke),
+            max_randomness_offset: Some(options.max_randomness_offset),
+            preserve_vertices: Some(options.preserve_vertices),
+            fill: Some(options.fill),
+            fill_style: Some(options.fill_style),
+            hachure_angle: Some(options.hachure_angle),
+            hachure_gap: Some(options.hachure_gap),
+            stroke_width: Some(options.stroke_width),
+            fill_weight: Some(options.fill_weight),
+            curve_step_count: Some(options.curve_step_count),
+            curve_fitting: Some(options.curve_fitting),
+            curve_tightness: Some(options.curve_tightness),
+            seed: Some(options.seed),
+            stroke_line_dash: Some(options.stroke_line_dash),
+            stroke_line_dash_offset: Some(options.stroke_line_dash_offset),
+            fill_line_dash: Some(options.fill_line_dash),
+            fill_line_dash_offset: Some(options.fill_line_dash_offset),
+            fill_roughness: Some(options.fill_roughness),
+            disable_multi_stroke_fill: Some(options.disable_multi_stroke_fill),
+            fill_shape_roughness_gain: Some(options.fill_shape_roughness_gain),
+            stroke_count: Some(options.stroke_count),
+            line_cap: Some(options.line_cap),
+            line_join: Some(options.line_join),
+            miter_limit: Some(options.miter_limit),
+            tolerance: Some(options.tolerance),
+            stroke: Some(options.stroke),
+            fill_color: Some(options.fill_color),
+        };
+    }
+}
+
+/// One drawing command. Points are absolute.
+///
+/// With the `serde` feature an op goes through `LegacyOp`, so it keeps the rough.js
+/// `{ "op": "bcurveTo", "data": [...] }` layout.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "LegacyOp", try_from = "LegacyOp"))]
+pub enum Op {
+    Move(Point2),
+    Line(Point2),
+    Cubic(Point2, Point2, Point2),
+}
+
+impl Op {
+    pub fn op_type(&self) -> OpType {
+        match self {
+            Op::Move(_) => Move,
+            Op::Line(_) => LineTo,
+            Op::Cubic(..) => BcurveTo,
+        }
+    }
+
+    /// The point the op ends at.
+    pub fn end(&self) -> Point2 {
+        match *self {
+            Op::Move(to) | Op::Line(to) | Op::Cubic(_, _, to) => to,
+        }
+    }
+}
+
+/// The rough.js layout of an op: its type and a flat list of coordinates, two for
+/// `move` and `lineTo` and six for `bcurveTo`.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegacyOp {
     pub op: OpType,
     pub data: Vec<f32>,
 }
 
+impl From<Op> for LegacyOp {
+    fn from(op: Op) -> Self {
+        let data = match op {
+            Op::Move(to) | Op::Line(to) => vec![to.x, to.y],
+            Op::Cubic(c1, c2, to) => vec![c1.x, c1.y, c2.x, c2.y, to.x, to.y],
+        };
+        return LegacyOp {
+            op: op.op_type(),
+            data,
+        };
+    }
+}
+
+impl TryFrom<LegacyOp> for Op {
+    type Error = Error;
+
+    fn try_from(legacy: LegacyOp) -> Result<Self> {
+        let expected = match legacy.op {
+            Move | LineTo => 2,
+            BcurveTo => 6,
+        };
+        if legacy.data.len() != expected {
+            return Err(Error::InvalidOpData {
+                op: legacy.op,
+                expected,
+                found: legacy.data.len(),
+            });
+        }
+        let d = &legacy.data;
+        return Ok(match legacy.op {
+            Move => Op::Move(pt2(d[0], d[1])),
+            LineTo => Op::Line(pt2(d[0], d[1])),
+            BcurveTo => Op::Cubic(pt2(d[0], d[1]), pt2(d[2], d[3]), pt2(d[4], d[5])),
+        });
+    }
+}
+
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OpSet {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
     pub ops_type: OpSetType,
     pub ops: Vec<Op>,
 }
 
+/// A generated shape: the options it was made with and the op sets to render.
+///
+/// With the `serde` feature this serializes to the same layout as a rough.js `Drawable`:
+///
+/// ```json
+/// {
+///   "shape": "line",
+///   "options": { "roughness": 1.0, "bowing": 1.5, "fillStyle": "hachure", ... },
+///   "sets": [
+///     { "type": "path", "ops": [{ "op": "move", "data": [0.0, 0.0] }, ...] }
+///   ]
+/// }
+/// ```
+///
+/// Op types are `move`, `bcurveTo` and `lineTo`, op set types are `path`, `fillPath` and
+/// `fillSketch`, and fill styles use the kebab-case rough.js names such as `cross-hatch`.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Drawable {
     pub shape: String,
     pub options: Options,
@@ -109,77 +941,201 @@ impl Drawable {
             sets,
         };
     }
+    /// Renders into a nannou `Draw`, painting each op set as `Drawable::render`
+    /// describes. The ops are read in place, so nothing is copied per frame.
     pub fn draw(&self, draw: &Draw) {
-        let sets = self.sets.clone();
-        let mut builder = nannou::geom::path::Builder::new().with_svg();
-        let mut weight = 3.0;
-        for drawing in sets.iter() {
-            match drawing.ops_type {
-                Path => {
-                    for item in drawing.ops.iter() {
-                        let data = item.data.clone();
-                        match item.op {
-                            Move => {
-                                builder.move_to([data[0], data[1]].into());
-                            }
-                            BcurveTo => {
-                                builder.cubic_bezier_to(
-                                    [data[0], data[1]].into(),
-                                    [data[2], data[3]].into(),
-                                    [data[4], data[5]].into(),
-                                );
-                            }
-                            LineTo => {
-                                builder.line_to([data[0], data[1]].into());
-                            }
-                        }
-                    }
-                }
-                FillPath => {
-                    // TODO: fill 対応
-                    for item in drawing.ops.iter() {
-                        let data = item.data.clone();
-                        match item.op {
-                            Move => {
-                                builder.move_to([data[0], data[1]].into());
-                            }
-                            BcurveTo => {
-                                builder.cubic_bezier_to(
-                                    [data[0], data[1]].into(),
-                                    [data[2], data[3]].into(),
-                                    [data[4], data[5]].into(),
-                                );
-                            }
-                            LineTo => {
-                                builder.line_to([data[0], data[1]].into());
-                            }
-                        }
-                    }
-                }
-                FillSketch => {
-                    weight = self.options.fill_weight;
-                    for item in drawing.ops.iter() {
-                        let data = item.data.clone();
-                        match item.op {
-                            Move => {
-                                builder.move_to([data[0], data[1]].into());
-                            }
-                            BcurveTo => {
-                                builder.cubic_bezier_to(
-                                    [data[0], data[1]].into(),
-                                    [data[2], data[3]].into(),
-                                    [data[4], data[5]].into(),
-                                );
-                            }
-                            LineTo => {
-                                builder.line_to([data[0], data[1]].into());
-                            }
-                        }
-                    }
-                }
-            }
+        let o = &self.options;
+        for set in self.sets.iter() {
+            draw_events(draw, OpEvents::new(&set.ops), Paint::of(set.ops_type, o), o);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_style_names_round_trip() {
+        let styles = [
+            FillStyle::Solid,
+            FillStyle::Zigzag,
+            FillStyle::CrossHatch,
+            FillStyle::Dots,
+            FillStyle::Dashed,
+            FillStyle::ZigzagLine,
+            FillStyle::Hachure,
+        ];
+        for style in styles {
+            assert_eq!(style.to_string().parse::<FillStyle>(), Ok(style));
+        }
+        assert_eq!(
+            "scribble".parse::<FillStyle>(),
+            Err(Error::UnknownFillStyle("scribble".to_string()))
+        );
+    }
+
+    #[test]
+    fn op_type_names_round_trip() {
+        for op in [OpType::Move, OpType::BcurveTo, OpType::LineTo] {
+            assert_eq!(op.to_string().parse::<OpType>(), Ok(op));
+        }
+        assert_eq!(
+            "arcTo".parse::<OpType>(),
+            Err(Error::UnknownOpType("arcTo".to_string()))
+        );
+    }
+
+    #[test]
+    fn op_set_type_names_round_trip() {
+        let types = [OpSetType::Path, OpSetType::FillPath, OpSetType::FillSketch];
+        for set_type in types {
+            assert_eq!(set_type.to_string().parse::<OpSetType>(), Ok(set_type));
+        }
+        assert_eq!(
+            "stroke".parse::<OpSetType>(),
+            Err(Error::UnknownOpSetType("stroke".to_string()))
+        );
+    }
+
+    fn invalid_option(result: Result<Options>) -> Option<&'static str> {
+        match result {
+            Err(Error::InvalidOption { name, .. }) => Some(name),
+            _ => None,
         }
-        let path = builder.build();
-        draw.path().stroke().weight(weight).events(path.iter());
+    }
+
+    #[test]
+    fn default_options_are_valid() {
+        assert!(Options::default().validate().is_ok());
+    }
+
+    #[test]
+    fn zero_hachure_gap_is_rejected() {
+        let result = Options::builder().hachure_gap(0.0).build();
+        assert_eq!(invalid_option(result), Some("hachure_gap"));
+        assert!(Options::builder().hachure_gap(-1.0).build().is_ok());
+    }
+
+    #[test]
+    fn curve_fitting_above_one_is_rejected() {
+        let result = Options::builder().curve_fitting(1.5).build();
+        assert_eq!(invalid_option(result), Some("curve_fitting"));
+    }
+
+    #[test]
+    fn zero_curve_step_count_is_rejected() {
+        let result = Options::builder().curve_step_count(0).build();
+        assert_eq!(invalid_option(result), Some("curve_step_count"));
+    }
+
+    #[test]
+    fn nan_fields_are_rejected() {
+        let builders: [(&str, OptionsBuilder); 5] = [
+            ("roughness", Options::builder().roughness(f32::NAN)),
+            ("bowing", Options::builder().bowing(f32::NAN)),
+            ("hachure_angle", Options::builder().hachure_angle(f32::NAN)),
+            ("stroke_width", Options::builder().stroke_width(f32::NAN)),
+            ("curve_fitting", Options::builder().curve_fitting(f32::NAN)),
+        ];
+        for (name, builder) in builders {
+            assert_eq!(invalid_option(builder.build()), Some(name));
+        }
+    }
+
+    #[test]
+    fn fill_multi_stroke_is_independent_of_outline() {
+        let options = Options {
+            disable_multi_stroke: true,
+            ..Options::default()
+        };
+        assert!(!options.fill_options().disable_multi_stroke);
+        let options = Options {
+            disable_multi_stroke_fill: true,
+            ..Options::default()
+        };
+        assert!(options.fill_options().disable_multi_stroke);
+    }
+
+    #[test]
+    fn ops_round_trip_through_legacy_layout() {
+        let ops = [
+            Op::Move(pt2(1.0, 2.0)),
+            Op::Line(pt2(-3.0, 4.5)),
+            Op::Cubic(pt2(0.0, 1.0), pt2(2.0, 3.0), pt2(4.0, 5.0)),
+        ];
+        for op in ops {
+            let legacy = LegacyOp::from(op);
+            assert_eq!(legacy.op, op.op_type());
+            assert_eq!(Op::try_from(legacy), Ok(op));
+        }
+        assert_eq!(
+            LegacyOp::from(Op::Cubic(pt2(0.0, 1.0), pt2(2.0, 3.0), pt2(4.0, 5.0))).data,
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+    }
+
+    #[test]
+    fn legacy_ops_with_wrong_lengths_are_rejected() {
+        let legacy = LegacyOp {
+            op: OpType::BcurveTo,
+            data: vec![0.0, 1.0],
+        };
+        assert_eq!(
+            Op::try_from(legacy),
+            Err(Error::InvalidOpData {
+                op: OpType::BcurveTo,
+                expected: 6,
+                found: 2
+            })
+        );
+        let legacy = LegacyOp {
+            op: OpType::Move,
+            data: vec![],
+        };
+        assert_eq!(
+            Op::try_from(legacy),
+            Err(Error::InvalidOpData {
+                op: OpType::Move,
+                expected: 2,
+                found: 0
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn drawables_serialize_in_the_rough_js_layout() {
+        let set = OpSet {
+            ops_type: OpSetType::Path,
+            ops: vec![
+                Op::Move(pt2(1.0, 2.0)),
+                Op::Cubic(pt2(3.0, 4.0), pt2(5.0, 6.0), pt2(7.0, 8.0)),
+            ],
+        };
+        let drawable = Drawable::new("line", Options::default(), vec![set]);
+        let json = serde_json::to_value(&drawable).unwrap();
+        assert_eq!(
+            json["sets"][0],
+            serde_json::json!({
+                "type": "path",
+                "ops": [
+                    { "op": "move", "data": [1.0, 2.0] },
+                    { "op": "bcurveTo", "data": [3.0, 4.0, 5.0, 6.0, 7.0, 8.0] },
+                ],
+            })
+        );
+
+        let parsed: Drawable = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.shape, "line");
+        assert_eq!(parsed.sets[0].ops, drawable.sets[0].ops);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn malformed_json_ops_are_rejected() {
+        let json = r#"{ "type": "path", "ops": [{ "op": "lineTo", "data": [1.0] }] }"#;
+        let error = serde_json::from_str::<OpSet>(json).err().unwrap();
+        assert!(error.to_string().contains("needs 2 coordinates, found 1"));
     }
 }
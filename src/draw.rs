@@ -0,0 +1,295 @@
+use nannou::prelude::*;
+
+use crate::core::{Color, Drawable, FillStyle, Options};
+use crate::error::Result;
+use crate::generator;
+
+/// Adds `draw.rough()` to nannou's `Draw`.
+///
+/// ```ignore
+/// draw.rough().rect().x_y(0.0, 0.0).w_h(200.0, 100.0).roughness(2.0).hachure().color(RED);
+/// ```
+///
+/// Shapes are generated and drawn when the builder is dropped, at the end of the
+/// statement, into the `Draw` they came from, so its translate, rotate and scale
+/// apply. A shape whose points or options are invalid is skipped; end the builder
+/// with `try_draw()` to get the error instead, or `build()` to keep the drawable.
+pub trait RoughDraw {
+    fn rough(&self) -> Rough<'_>;
+}
+
+impl RoughDraw for Draw {
+    fn rough(&self) -> Rough<'_> {
+        return Rough {
+            draw: self,
+            options: Options::new(),
+        };
+    }
+}
+
+/// Picks the shape to draw. Options set here are the starting point for it.
+pub struct Rough<'a> {
+    draw: &'a Draw,
+    options: Options,
+}
+
+impl<'a> Rough<'a> {
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    fn primitive(self, kind: Kind) -> RoughPrimitive<'a> {
+        return RoughPrimitive {
+            draw: self.draw,
+            options: self.options,
+            kind,
+            xy: pt2(0.0, 0.0),
+            wh: pt2(100.0, 100.0),
+            points: vec![],
+            finished: false,
+        };
+    }
+
+    pub fn rect(self) -> RoughPrimitive<'a> {
+        self.primitive(Kind::Rect)
+    }
+
+    pub fn ellipse(self) -> RoughPrimitive<'a> {
+        self.primitive(Kind::Ellipse)
+    }
+
+    /// A line from `start` to `end`.
+    pub fn line(self) -> RoughPrimitive<'a> {
+        self.primitive(Kind::Line)
+    }
+
+    /// An open path through `points`.
+    pub fn polyline(self) -> RoughPrimitive<'a> {
+        self.primitive(Kind::Polyline)
+    }
+
+    /// A closed shape through `points`.
+    pub fn polygon(self) -> RoughPrimitive<'a> {
+        self.primitive(Kind::Polygon)
+    }
+
+    /// A polygon through the first three `points`.
+    pub fn tri(self) -> RoughPrimitive<'a> {
+        self.primitive(Kind::Tri)
+    }
+
+    /// A polygon through the first four `points`.
+    pub fn quad(self) -> RoughPrimitive<'a> {
+        self.primitive(Kind::Quad)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Kind {
+    Rect,
+    Ellipse,
+    Line,
+    Polyline,
+    Polygon,
+    Tri,
+    Quad,
+}
+
+/// A rough shape being built. `x_y` and `w_h` place rects and ellipses, `start` and
+/// `end` place lines, and `points` places the rest.
+pub struct RoughPrimitive<'a> {
+    draw: &'a Draw,
+    options: Options,
+    kind: Kind,
+    xy: Point2,
+    wh: Point2,
+    points: Vec<Point2>,
+    finished: bool,
+}
+
+impl RoughPrimitive<'_> {
+    pub fn x_y(mut self, x: f32, y: f32) -> Self {
+        self.xy = pt2(x, y);
+        self
+    }
+
+    pub fn xy(self, xy: Point2) -> Self {
+        self.x_y(xy.x, xy.y)
+    }
+
+    pub fn w_h(mut self, w: f32, h: f32) -> Self {
+        self.wh = pt2(w, h);
+        self
+    }
+
+    pub fn wh(self, wh: Point2) -> Self {
+        self.w_h(wh.x, wh.y)
+    }
+
+    pub fn start(mut self, start: Point2) -> Self {
+        self.points.resize(2, pt2(0.0, 0.0));
+        self.points[0] = start;
+        self
+    }
+
+    pub fn end(mut self, end: Point2) -> Self {
+        self.points.resize(2, pt2(0.0, 0.0));
+        self.points[1] = end;
+        self
+    }
+
+    pub fn points<I: IntoIterator<Item = Point2>>(mut self, points: I) -> Self {
+        self.points = Vec::from_iter(points);
+        self
+    }
+
+    pub fn roughness(mut self, roughness: f32) -> Self {
+        self.options.roughness = roughness;
+        self
+    }
+
+    pub fn bowing(mut self, bowing: f32) -> Self {
+        self.options.bowing = bowing;
+        self
+    }
+
+    pub fn seed(mut self, seed: u32) -> Self {
+        self.options.seed = seed;
+        self
+    }
+
+    /// The outline color.
+    pub fn color<C: Into<Color>>(mut self, color: C) -> Self {
+        self.options.stroke = color.into();
+        self
+    }
+
+    pub fn stroke_weight(mut self, weight: f32) -> Self {
+        self.options.stroke_width = weight;
+        self
+    }
+
+    pub fn fill_color<C: Into<Color>>(mut self, color: C) -> Self {
+        self.options.fill_color = color.into();
+        self
+    }
+
+    pub fn fill_weight(mut self, weight: f32) -> Self {
+        self.options.fill_weight = weight;
+        self
+    }
+
+    /// Turns on the fill with `style`.
+    pub fn fill_style(mut self, style: FillStyle) -> Self {
+        self.options.fill = true;
+        self.options.fill_style = style;
+        self
+    }
+
+    pub fn hachure(self) -> Self {
+        self.fill_style(FillStyle::Hachure)
+    }
+
+    pub fn cross_hatch(self) -> Self {
+        self.fill_style(FillStyle::CrossHatch)
+    }
+
+    pub fn zigzag(self) -> Self {
+        self.fill_style(FillStyle::Zigzag)
+    }
+
+    pub fn solid(self) -> Self {
+        self.fill_style(FillStyle::Solid)
+    }
+
+    pub fn hachure_angle(mut self, angle: f32) -> Self {
+        self.options.hachure_angle = angle;
+        self
+    }
+
+    pub fn hachure_gap(mut self, gap: f32) -> Self {
+        self.options.hachure_gap = gap;
+        self
+    }
+
+    /// Generates and draws the shape now, returning the error if its points or
+    /// options are invalid.
+    pub fn try_draw(mut self) -> Result<()> {
+        self.finished = true;
+        let drawable = self.generate()?;
+        drawable.draw(self.draw);
+        return Ok(());
+    }
+
+    /// Generates the shape without drawing it.
+    pub fn build(mut self) -> Result<Drawable> {
+        self.finished = true;
+        return self.generate();
+    }
+
+    fn generate(&self) -> Result<Drawable> {
+        let options = self.options.clone();
+        let (x, y) = (self.xy.x, self.xy.y);
+        let (w, h) = (self.wh.x, self.wh.y);
+        match self.kind {
+            Kind::Rect => generator::rectangle(x - w / 2.0, y - h / 2.0, w, h, options),
+            Kind::Ellipse => generator::ellipse(x, y, w, h, options),
+            Kind::Line => {
+                let start = self.points.first().copied().unwrap_or(pt2(0.0, 0.0));
+                let end = self.points.get(1).copied().unwrap_or(pt2(0.0, 0.0));
+                generator::line(start.x, start.y, end.x, end.y, options)
+            }
+            Kind::Polyline => generator::linear_path(self.points.clone(), options),
+            Kind::Polygon => generator::polygon(self.points.clone(), options),
+            Kind::Tri => generator::polygon(self.points.iter().take(3).copied().collect(), options),
+            Kind::Quad => {
+                generator::polygon(self.points.iter().take(4).copied().collect(), options)
+            }
+        }
+    }
+}
+
+impl Drop for RoughPrimitive<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        if let Ok(drawable) = self.generate() {
+            drawable.draw(self.draw);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn build_returns_the_drawable() {
+        let draw = Draw::new();
+        let drawable = draw.rough().rect().w_h(40.0, 20.0).seed(3).build();
+        assert_eq!(
+            drawable.map(|d| d.shape).ok(),
+            Some("rectangle".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_shapes_report_errors() {
+        let draw = Draw::new();
+        let points = [pt2(0.0, 0.0), pt2(10.0, 0.0)];
+        assert_eq!(
+            draw.rough().tri().points(points).try_draw(),
+            Err(Error::NotEnoughPoints {
+                required: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            draw.rough().ellipse().w_h(0.0, 10.0).build().err(),
+            Some(Error::ZeroSize)
+        );
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+
+use crate::core::{FillStyle, OpType};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// A coordinate, size or angle is NaN or infinite.
+    NonFiniteValue,
+    /// A shape was given fewer points than it needs.
+    NotEnoughPoints {
+        required: usize,
+        found: usize,
+    },
+    /// Every point of a polygon lies on the same line, so it encloses no area.
+    CollinearPoints,
+    /// A rectangle, ellipse or arc has zero width or height.
+    ZeroSize,
+    /// An arc whose stop angle is not after its start angle.
+    EmptyArc,
+    UnknownFillStyle(String),
+    UnsupportedFillStyle(FillStyle),
+    UnknownOpType(String),
+    UnknownOpSetType(String),
+    /// A legacy op has the wrong number of coordinates for its type.
+    InvalidOpData {
+        op: OpType,
+        expected: usize,
+        found: usize,
+    },
+    /// An `Options` field is outside the range the renderer supports.
+    InvalidOption {
+        name: &'static str,
+        expected: &'static str,
+    },
+    UnknownPreset(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NonFiniteValue => write!(f, "value is NaN or infinite"),
+            Error::NotEnoughPoints { required, found } => {
+                write!(f, "expected at least {} points, found {}", required, found)
+            }
+            Error::CollinearPoints => write!(f, "polygon points are collinear"),
+            Error::ZeroSize => write!(f, "shape has zero width or height"),
+            Error::EmptyArc => write!(f, "arc stop angle must be greater than its start angle"),
+            Error::UnknownFillStyle(name) => write!(f, "unknown fill style {:?}", name),
+            Error::UnsupportedFillStyle(style) => {
+                write!(f, "fill style \"{}\" is not supported", style)
+            }
+            Error::UnknownOpType(name) => write!(f, "unknown op type {:?}", name),
+            Error::UnknownOpSetType(name) => write!(f, "unknown op set type {:?}", name),
+            Error::InvalidOpData {
+                op,
+                expected,
+                found,
+            } => write!(
+                f,
+                "op \"{}\" needs {} coordinates, found {}",
+                op, expected, found
+            ),
+            Error::InvalidOption { name, expected } => {
+                write!(f, "option `{}` must be {}", name, expected)
+            }
+            Error::UnknownPreset(name) => write!(f, "unknown preset {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -2,8 +2,9 @@ use std::cmp::Ordering;
 
 use nannou::prelude::*;
 
-use crate::core::{FillStyle, OpSet, OpSetType, Options};
-use crate::renderer::_double_line;
+use crate::core::{FillStyle, Op, OpSet, OpSetType, Options};
+use crate::error::{Error, Result};
+use crate::renderer::{_dashed_lines, _double_line};
 
 use Ordering::*;
 
@@ -13,6 +14,7 @@ pub trait PatternFiller {
 
 pub struct HachureFiller {}
 pub struct ZigzagFiller {}
+pub struct CrossHatchFiller {}
 
 struct Line {
     start: Point2,
@@ -67,41 +69,71 @@ fn rotate_points(points: Vec<Point2>, center: Point2, degrees: f32) -> Vec<Point
     )
 }
 
-pub fn get_filler(options: &Options) -> Box<dyn PatternFiller> {
+pub fn get_filler(options: &Options) -> Result<Box<dyn PatternFiller>> {
     match options.fill_style {
-        FillStyle::Hachure => Box::new(HachureFiller {}),
-        FillStyle::Zigzag => Box::new(ZigzagFiller {}),
-        FillStyle::Solid => unreachable!(),
-        _ => unimplemented!(),
+        FillStyle::Hachure => Ok(Box::new(HachureFiller {})),
+        FillStyle::Zigzag => Ok(Box::new(ZigzagFiller {})),
+        FillStyle::CrossHatch => Ok(Box::new(CrossHatchFiller {})),
+        style => Err(Error::UnsupportedFillStyle(style)),
     }
 }
 
 impl PatternFiller for HachureFiller {
     fn fill_polygons(&self, polygon_list: Vec<Vec<Point2>>, o: &Options) -> OpSet {
         let lines = polygon_hachure_lines(polygon_list, o);
-        let ops = Vec::from_iter(
-            lines
-                .iter()
-                .map(|l| _double_line(l.start.x, l.start.y, l.end.x, l.end.y, o)),
-        )
-        .into_iter()
-        .flatten()
-        .collect();
         return OpSet {
             ops_type: OpSetType::FillSketch,
-            ops,
+            ops: render_lines(&lines, o),
         };
     }
 }
 
-fn polygon_hachure_lines(polygon_list: Vec<Vec<Point2>>, o: &Options) -> Vec<Line> {
-    let angle = o.hachure_angle + 90.0;
-    let mut gap = if o.hachure_gap < 0.0 {
+impl PatternFiller for CrossHatchFiller {
+    fn fill_polygons(&self, polygon_list: Vec<Vec<Point2>>, o: &Options) -> OpSet {
+        let mut set = HachureFiller {}.fill_polygons(polygon_list.clone(), o);
+        let crossed = Options {
+            hachure_angle: o.hachure_angle + 90.0,
+            ..o.clone()
+        };
+        set.ops
+            .extend(HachureFiller {}.fill_polygons(polygon_list, &crossed).ops);
+        return set;
+    }
+}
+
+/// Draws each line rough with the fill options, broken into dashes when
+/// `fill_line_dash` is set. Every line starts its dash pattern afresh at
+/// `fill_line_dash_offset`.
+fn render_lines(lines: &[Line], o: &Options) -> Vec<Op> {
+    let o = &o.fill_options();
+    let mut ops = vec![];
+    for l in lines.iter() {
+        if o.fill_line_dash.is_empty() {
+            ops.extend(_double_line(l.start.x, l.start.y, l.end.x, l.end.y, o));
+        } else {
+            ops.extend(_dashed_lines(
+                &[l.start, l.end],
+                &o.fill_line_dash,
+                o.fill_line_dash_offset,
+                o,
+            ));
+        }
+    }
+    return ops;
+}
+
+fn hachure_gap(o: &Options) -> f32 {
+    let gap = if o.hachure_gap < 0.0 {
         o.stroke_width * 4.0
     } else {
         o.hachure_gap
     };
-    gap = gap.max(0.1);
+    return gap.max(0.1);
+}
+
+fn polygon_hachure_lines(polygon_list: Vec<Vec<Point2>>, o: &Options) -> Vec<Line> {
+    let angle = o.hachure_angle + 90.0;
+    let gap = hachure_gap(o);
 
     let rotation_center = pt2(0.0, 0.0);
     let rotate_polygon_list = Vec::from_iter(
@@ -117,6 +149,9 @@ fn straight_hachure_lines(polygon_list: Vec<Vec<Point2>>, gap: f32) -> Vec<Line>
     let mut vertex_array: Vec<Vec<Point2>> = vec![];
     for polygon in polygon_list.iter() {
         let mut vertices = polygon.clone();
+        if vertices.is_empty() {
+            continue;
+        }
         if vertices[0] != vertices[vertices.len() - 1] {
             vertices.push(vertices[0].clone());
         }
@@ -148,10 +183,14 @@ fn straight_hachure_lines(polygon_list: Vec<Vec<Point2>>, gap: f32) -> Vec<Line>
 
     edges.sort_by(|e1, e2| cmp_edge(e1, e2));
 
+    let mut lines: Vec<Line> = vec![];
+    if edges.is_empty() {
+        return lines;
+    }
+
     let mut active_edges: Vec<EdgeEntry> = vec![];
     let mut y = edges[0].ymin;
 
-    let mut lines: Vec<Line> = vec![];
     let gap = gap.max(0.1);
 
     while active_edges.len() > 0 || edges.len() > 0 {
@@ -203,6 +242,29 @@ fn straight_hachure_lines(polygon_list: Vec<Vec<Point2>>, gap: f32) -> Vec<Line>
 
 impl PatternFiller for ZigzagFiller {
     fn fill_polygons(&self, polygon_list: Vec<Vec<Point2>>, o: &Options) -> OpSet {
-        unimplemented!()
+        let gap = hachure_gap(o);
+        let lines = polygon_hachure_lines(polygon_list, o);
+        let zigzag_angle = (f32::PI() / 180.0) * o.hachure_angle;
+        let dg = pt2(
+            gap * 0.5 * zigzag_angle.cos(),
+            -gap * 0.5 * zigzag_angle.sin(),
+        );
+        let mut zigzag_lines = vec![];
+        for l in lines.iter() {
+            if l.start != l.end {
+                zigzag_lines.push(Line {
+                    start: l.start - dg,
+                    end: l.end,
+                });
+                zigzag_lines.push(Line {
+                    start: l.start + dg,
+                    end: l.end,
+                });
+            }
+        }
+        return OpSet {
+            ops_type: OpSetType::FillSketch,
+            ops: render_lines(&zigzag_lines, o),
+        };
     }
 }
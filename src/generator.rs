@@ -1,21 +1,302 @@
+use nannou::geom::path::Path;
+use nannou::lyon::path::PathEvent;
 use nannou::prelude::*;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
-use crate::core::{Drawable, FillStyle, OpSet, Options};
+use crate::core::{Drawable, FillStyle, OpSet, OpSetType, Options, PartialOptions};
+use crate::error::{Error, Result};
+use crate::random::{random, with_seed};
 use crate::renderer::{
-    arc, ellipse_with_params, generate_ellipse_params, line, linear_path, pattern_fill_arc,
-    pattern_fill_polygon, rectangle, solid_fill_polygon,
+    self, ellipse_with_params, generate_ellipse_params, pattern_fill_arc, pattern_fill_polygon,
+    solid_fill_polygon,
 };
 
-pub struct RoughGenerator {}
+/// Generates drawables from a set of default options, like rough.js `rough.generator(config)`.
+///
+/// When the default options carry a non-zero seed, every shape gets the next seed in
+/// sequence, so each shape has its own randomness and a scene is reproducible.
+pub struct RoughGenerator {
+    options: Options,
+    seed: u32,
+}
 
 impl RoughGenerator {
-    pub fn line(x1: f32, y1: f32, x2: f32, y2: f32, options: Options) -> Drawable {
-        Drawable::new("line", options, vec![line(x1, y1, x2, y2, &options)])
+    pub fn new(options: Options) -> Self {
+        return RoughGenerator {
+            seed: options.seed,
+            options,
+        };
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Merges `overrides` into the defaults used for every following shape.
+    pub fn merge_options(&mut self, overrides: &PartialOptions) {
+        self.options = self.options.merge(overrides);
+        if let Some(seed) = overrides.seed {
+            self.seed = seed;
+        }
+    }
+
+    fn next_options(&mut self, overrides: Option<&PartialOptions>) -> Options {
+        let mut options = match overrides {
+            Some(partial) => self.options.merge(partial),
+            None => self.options.clone(),
+        };
+        let seed_overridden = overrides.is_some_and(|partial| partial.seed.is_some());
+        if !seed_overridden && self.seed != 0 {
+            options.seed = self.seed;
+            self.seed = self.seed.wrapping_add(1).max(1);
+        }
+        return options;
+    }
+
+    pub fn line(
+        &mut self,
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        line(x1, y1, x2, y2, self.next_options(overrides))
+    }
+
+    pub fn rectangle(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        rectangle(x, y, width, height, self.next_options(overrides))
+    }
+
+    pub fn ellipse(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        ellipse(x, y, width, height, self.next_options(overrides))
+    }
+
+    pub fn linear_path(
+        &mut self,
+        points: Vec<Point2>,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        linear_path(points, self.next_options(overrides))
+    }
+
+    pub fn polygon(
+        &mut self,
+        points: Vec<Point2>,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        polygon(points, self.next_options(overrides))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        start: f32,
+        stop: f32,
+        closed: bool,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        arc(
+            x,
+            y,
+            width,
+            height,
+            start,
+            stop,
+            closed,
+            self.next_options(overrides),
+        )
     }
 
-    pub fn rectangle(x: f32, y: f32, width: f32, height: f32, options: Options) -> Drawable {
+    pub fn quadratic_bezier(
+        &mut self,
+        start: Point2,
+        control: Point2,
+        end: Point2,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        quadratic_bezier(start, control, end, self.next_options(overrides))
+    }
+
+    pub fn cubic_bezier(
+        &mut self,
+        start: Point2,
+        control1: Point2,
+        control2: Point2,
+        end: Point2,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        cubic_bezier(start, control1, control2, end, self.next_options(overrides))
+    }
+
+    pub fn path(
+        &mut self,
+        lyon_path: &Path,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable> {
+        path(lyon_path, self.next_options(overrides))
+    }
+
+    pub fn path_events<I>(
+        &mut self,
+        events: I,
+        overrides: Option<&PartialOptions>,
+    ) -> Result<Drawable>
+    where
+        I: IntoIterator<Item = PathEvent>,
+    {
+        path_events(events, self.next_options(overrides))
+    }
+
+    /// Generates every shape in `shapes`, in parallel with the `rayon` feature.
+    ///
+    /// Each shape gets its options, seed included, before any of them is generated,
+    /// so the result does not depend on how the work is scheduled. A seeded generator
+    /// gives the same drawables as calling the shape methods one by one; an unseeded
+    /// one draws a fresh seed for each shape.
+    pub fn generate_all(&mut self, shapes: &[Shape]) -> Vec<Result<Drawable>> {
+        let jobs = Vec::from_iter(shapes.iter().map(|shape| {
+            let mut options = self.next_options(None);
+            if options.seed == 0 {
+                options.seed = 1 + (random() * (i32::MAX - 1) as f32) as u32;
+            }
+            (shape, options)
+        }));
+        #[cfg(feature = "rayon")]
+        let iter = jobs.into_par_iter();
+        #[cfg(not(feature = "rayon"))]
+        let iter = jobs.into_iter();
+        return iter
+            .map(|(shape, options)| shape.generate(options))
+            .collect();
+    }
+}
+
+/// A shape to generate later, for batch APIs such as `RoughGenerator::generate_all`.
+/// The fields match the arguments of the generator function of the same name.
+#[derive(Clone, Debug)]
+pub enum Shape {
+    Line {
+        x1: f32,
+        y1: f32,
+        x2: f32,
+        y2: f32,
+    },
+    Rectangle {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    Ellipse {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    LinearPath(Vec<Point2>),
+    Polygon(Vec<Point2>),
+    Arc {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        start: f32,
+        stop: f32,
+        closed: bool,
+    },
+    QuadraticBezier {
+        start: Point2,
+        control: Point2,
+        end: Point2,
+    },
+    CubicBezier {
+        start: Point2,
+        control1: Point2,
+        control2: Point2,
+        end: Point2,
+    },
+    Path(Path),
+}
+
+impl Shape {
+    pub fn generate(&self, options: Options) -> Result<Drawable> {
+        match self {
+            Shape::Line { x1, y1, x2, y2 } => line(*x1, *y1, *x2, *y2, options),
+            Shape::Rectangle {
+                x,
+                y,
+                width,
+                height,
+            } => rectangle(*x, *y, *width, *height, options),
+            Shape::Ellipse {
+                x,
+                y,
+                width,
+                height,
+            } => ellipse(*x, *y, *width, *height, options),
+            Shape::LinearPath(points) => linear_path(points.clone(), options),
+            Shape::Polygon(points) => polygon(points.clone(), options),
+            Shape::Arc {
+                x,
+                y,
+                width,
+                height,
+                start,
+                stop,
+                closed,
+            } => arc(*x, *y, *width, *height, *start, *stop, *closed, options),
+            Shape::QuadraticBezier {
+                start,
+                control,
+                end,
+            } => quadratic_bezier(*start, *control, *end, options),
+            Shape::CubicBezier {
+                start,
+                control1,
+                control2,
+                end,
+            } => cubic_bezier(*start, *control1, *control2, *end, options),
+            Shape::Path(lyon_path) => path(lyon_path, options),
+        }
+    }
+}
+
+pub fn line(x1: f32, y1: f32, x2: f32, y2: f32, options: Options) -> Result<Drawable> {
+    options.validate()?;
+    check_finite(&[x1, y1, x2, y2])?;
+    with_seed(options.seed, || {
+        let path = renderer::line(x1, y1, x2, y2, &options);
+        Ok(Drawable::new("line", options, vec![path]))
+    })
+}
+
+pub fn rectangle(x: f32, y: f32, width: f32, height: f32, options: Options) -> Result<Drawable> {
+    options.validate()?;
+    check_finite(&[x, y, width, height])?;
+    check_size(width, height)?;
+    with_seed(options.seed, || {
         let mut paths: Vec<OpSet> = vec![];
-        let outline = rectangle(x, y, width, height, &options);
+        let outline = renderer::rectangle(x, y, width, height, &options);
         if options.fill {
             let points = vec![
                 pt2(x, y),
@@ -23,83 +304,374 @@ impl RoughGenerator {
                 pt2(x + width, y + height),
                 pt2(x, y + height),
             ];
-            match options.fill_style {
-                FillStyle::Solid => {
-                    paths.push(solid_fill_polygon(vec![points], &options));
-                }
-                _ => {
-                    paths.push(pattern_fill_polygon(vec![points], &options));
-                }
-            }
+            paths.push(fill_polygons(vec![points], &options)?);
         }
         paths.push(outline);
-        Drawable::new("rectangle", options, paths)
-    }
+        Ok(Drawable::new("rectangle", options, paths))
+    })
+}
 
-    pub fn ellipse(x: f32, y: f32, width: f32, height: f32, options: Options) -> Drawable {
+pub fn ellipse(x: f32, y: f32, width: f32, height: f32, options: Options) -> Result<Drawable> {
+    options.validate()?;
+    check_finite(&[x, y, width, height])?;
+    check_size(width, height)?;
+    with_seed(options.seed, || {
         let mut paths: Vec<OpSet> = vec![];
         let ellipse_params = generate_ellipse_params(width, height, &options);
         let ellipse_response = ellipse_with_params(x, y, &options, ellipse_params);
         if options.fill {
             match options.fill_style {
                 FillStyle::Solid => {
-                    let shape = ellipse_with_params(x, y, &options, ellipse_params).opset;
+                    let mut shape =
+                        ellipse_with_params(x, y, &solid_fill_options(&options), ellipse_params)
+                            .opset;
+                    shape.ops_type = OpSetType::FillPath;
                     paths.push(shape);
                 }
                 _ => {
                     let shape =
-                        pattern_fill_polygon(vec![ellipse_response.estimated_points], &options);
+                        pattern_fill_polygon(vec![ellipse_response.estimated_points], &options)?;
                     paths.push(shape);
                 }
             }
         }
         paths.push(ellipse_response.opset);
-        return Drawable::new("ellipse", options, paths);
-    }
+        return Ok(Drawable::new("ellipse", options, paths));
+    })
+}
 
-    pub fn linear_path(points: Vec<Point2>, options: Options) -> Drawable {
-        let path = linear_path(points, false, &options);
-        return Drawable::new("linear_path", options, vec![path]);
-    }
+pub fn linear_path(points: Vec<Point2>, options: Options) -> Result<Drawable> {
+    options.validate()?;
+    check_points(&points, 2)?;
+    with_seed(options.seed, || {
+        let path = renderer::linear_path(points, false, &options);
+        return Ok(Drawable::new("linear_path", options, vec![path]));
+    })
+}
 
-    pub fn polygon(points: Vec<Point2>, options: Options) -> Drawable {
+pub fn polygon(points: Vec<Point2>, options: Options) -> Result<Drawable> {
+    options.validate()?;
+    check_points(&points, 3)?;
+    check_not_collinear(&points)?;
+    with_seed(options.seed, || {
         let mut paths: Vec<OpSet> = vec![];
-        let outline = linear_path(points.clone(), true, &options);
+        let outline = renderer::linear_path(points.clone(), true, &options);
         if options.fill {
-            match options.fill_style {
-                FillStyle::Solid => paths.push(solid_fill_polygon(vec![points.clone()], &options)),
-                _ => paths.push(pattern_fill_polygon(vec![points.clone()], &options)),
-            }
+            paths.push(fill_polygons(vec![points.clone()], &options)?);
         }
         // TODO: add options.stroke
         paths.push(outline);
-        return Drawable::new("polygon", options, paths);
-    }
+        return Ok(Drawable::new("polygon", options, paths));
+    })
+}
 
-    pub fn arc(
-        x: f32,
-        y: f32,
-        width: f32,
-        height: f32,
-        start: f32,
-        stop: f32,
-        closed: bool,
-        options: Options,
-    ) -> Drawable {
+#[allow(clippy::too_many_arguments)]
+pub fn arc(
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    start: f32,
+    stop: f32,
+    closed: bool,
+    options: Options,
+) -> Result<Drawable> {
+    options.validate()?;
+    check_finite(&[x, y, width, height, start, stop])?;
+    check_size(width, height)?;
+    with_seed(options.seed, || {
         let mut paths: Vec<OpSet> = vec![];
-        let outline = arc(x, y, width, height, start, stop, closed, true, &options);
+        let outline = renderer::arc(x, y, width, height, start, stop, closed, true, &options)?;
         if closed && options.fill {
             match options.fill_style {
                 FillStyle::Solid => {
-                    unimplemented!()
+                    let fill_options = solid_fill_options(&options);
+                    let mut shape = renderer::arc(
+                        x,
+                        y,
+                        width,
+                        height,
+                        start,
+                        stop,
+                        true,
+                        false,
+                        &fill_options,
+                    )?;
+                    shape.ops_type = OpSetType::FillPath;
+                    paths.push(shape);
                 }
                 _ => {
-                    paths.push(pattern_fill_arc(x, y, width, height, start, stop, &options));
+                    paths.push(pattern_fill_arc(
+                        x, y, width, height, start, stop, &options,
+                    )?);
                 }
             }
         }
         // TODO: stroke
         paths.push(outline);
-        return Drawable::new("arc", options, paths);
+        return Ok(Drawable::new("arc", options, paths));
+    })
+}
+
+pub fn quadratic_bezier(
+    start: Point2,
+    control: Point2,
+    end: Point2,
+    options: Options,
+) -> Result<Drawable> {
+    options.validate()?;
+    check_points(&[start, control, end], 3)?;
+    with_seed(options.seed, || {
+        let path = renderer::quadratic_bezier(start, control, end, &options);
+        return Ok(Drawable::new("quadratic_bezier", options, vec![path]));
+    })
+}
+
+pub fn cubic_bezier(
+    start: Point2,
+    control1: Point2,
+    control2: Point2,
+    end: Point2,
+    options: Options,
+) -> Result<Drawable> {
+    options.validate()?;
+    check_points(&[start, control1, control2, end], 4)?;
+    with_seed(options.seed, || {
+        let path = renderer::cubic_bezier(start, control1, control2, end, &options);
+        return Ok(Drawable::new("cubic_bezier", options, vec![path]));
+    })
+}
+
+pub fn path(lyon_path: &Path, options: Options) -> Result<Drawable> {
+    return path_events(lyon_path.iter(), options);
+}
+
+pub fn path_events<I>(events: I, options: Options) -> Result<Drawable>
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    options.validate()?;
+    with_seed(options.seed, || {
+        let mut paths: Vec<OpSet> = vec![];
+        let result = renderer::path(events, &options);
+        if options.fill && !result.closed_polygons.is_empty() {
+            paths.push(fill_polygons(result.closed_polygons, &options)?);
+        }
+        paths.push(result.opset);
+        return Ok(Drawable::new("path", options, paths));
+    })
+}
+
+fn fill_polygons(polygon_list: Vec<Vec<Point2>>, options: &Options) -> Result<OpSet> {
+    match options.fill_style {
+        FillStyle::Solid => Ok(solid_fill_polygon(
+            polygon_list,
+            &solid_fill_options(options),
+        )),
+        _ => pattern_fill_polygon(polygon_list, options),
+    }
+}
+
+/// Solid fill shapes wobble with the fill roughness scaled by
+/// `fill_shape_roughness_gain`. They are closed, so they never take the outline's
+/// dash pattern.
+fn solid_fill_options(options: &Options) -> Options {
+    let fill_options = options.fill_options();
+    Options {
+        roughness: fill_options.roughness * options.fill_shape_roughness_gain,
+        stroke_line_dash: vec![],
+        ..fill_options
+    }
+}
+
+fn check_finite(values: &[f32]) -> Result<()> {
+    if values.iter().all(|v| v.is_finite()) {
+        Ok(())
+    } else {
+        Err(Error::NonFiniteValue)
+    }
+}
+
+fn check_size(width: f32, height: f32) -> Result<()> {
+    if width == 0.0 || height == 0.0 {
+        Err(Error::ZeroSize)
+    } else {
+        Ok(())
+    }
+}
+
+fn check_points(points: &[Point2], required: usize) -> Result<()> {
+    if points.len() < required {
+        return Err(Error::NotEnoughPoints {
+            required,
+            found: points.len(),
+        });
+    }
+    if !points.iter().all(|p| p.is_finite()) {
+        return Err(Error::NonFiniteValue);
+    }
+    Ok(())
+}
+
+fn check_not_collinear(points: &[Point2]) -> Result<()> {
+    let origin = points[0];
+    let direction = match points.iter().find(|p| **p != origin) {
+        Some(p) => *p - origin,
+        None => return Err(Error::CollinearPoints),
+    };
+    let tolerance = f32::EPSILON * direction.length();
+    if points
+        .iter()
+        .any(|p| direction.perp_dot(*p - origin).abs() > tolerance * (*p - origin).length())
+    {
+        Ok(())
+    } else {
+        Err(Error::CollinearPoints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Op;
+
+    fn options() -> Options {
+        return Options {
+            seed: 1,
+            ..Options::default()
+        };
+    }
+
+    #[test]
+    fn nan_coordinates_are_rejected() {
+        assert_eq!(
+            line(0.0, f32::NAN, 10.0, 10.0, options()).err(),
+            Some(Error::NonFiniteValue)
+        );
+        assert_eq!(
+            linear_path(vec![pt2(0.0, 0.0), pt2(f32::INFINITY, 1.0)], options()).err(),
+            Some(Error::NonFiniteValue)
+        );
+    }
+
+    #[test]
+    fn missing_points_are_rejected() {
+        assert_eq!(
+            linear_path(vec![], options()).err(),
+            Some(Error::NotEnoughPoints {
+                required: 2,
+                found: 0
+            })
+        );
+        assert_eq!(
+            polygon(vec![pt2(0.0, 0.0), pt2(1.0, 1.0)], options()).err(),
+            Some(Error::NotEnoughPoints {
+                required: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn collinear_polygons_are_rejected() {
+        let points = vec![pt2(0.0, 0.0), pt2(1.0, 1.0), pt2(2.0, 2.0)];
+        assert_eq!(
+            polygon(points, options()).err(),
+            Some(Error::CollinearPoints)
+        );
+        let points = vec![pt2(3.0, 3.0); 3];
+        assert_eq!(
+            polygon(points, options()).err(),
+            Some(Error::CollinearPoints)
+        );
+    }
+
+    #[test]
+    fn zero_sized_shapes_are_rejected() {
+        assert_eq!(
+            rectangle(0.0, 0.0, 0.0, 10.0, options()).err(),
+            Some(Error::ZeroSize)
+        );
+        assert_eq!(
+            ellipse(0.0, 0.0, 10.0, 0.0, options()).err(),
+            Some(Error::ZeroSize)
+        );
+    }
+
+    #[test]
+    fn empty_arcs_are_rejected() {
+        assert_eq!(
+            arc(0.0, 0.0, 10.0, 10.0, 1.0, 1.0, false, options()).err(),
+            Some(Error::EmptyArc)
+        );
+        assert_eq!(
+            arc(0.0, 0.0, 10.0, 10.0, 2.0, 1.0, true, options()).err(),
+            Some(Error::EmptyArc)
+        );
+    }
+
+    #[test]
+    fn unsupported_fill_styles_are_rejected() {
+        let options = Options {
+            fill: true,
+            fill_style: FillStyle::Dots,
+            ..options()
+        };
+        assert_eq!(
+            rectangle(0.0, 0.0, 10.0, 10.0, options).err(),
+            Some(Error::UnsupportedFillStyle(FillStyle::Dots))
+        );
+    }
+
+    fn sets(result: Result<Drawable>) -> Vec<(OpSetType, Vec<Op>)> {
+        let drawable = match result {
+            Ok(drawable) => drawable,
+            Err(error) => panic!("shape should generate: {}", error),
+        };
+        return Vec::from_iter(drawable.sets.into_iter().map(|set| (set.ops_type, set.ops)));
+    }
+
+    #[test]
+    fn seeded_generate_all_matches_sequential_calls() {
+        let defaults = Options {
+            seed: 42,
+            fill: true,
+            ..Options::default()
+        };
+        let shapes = [
+            Shape::Line {
+                x1: 0.0,
+                y1: 0.0,
+                x2: 50.0,
+                y2: 20.0,
+            },
+            Shape::Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: 40.0,
+                height: 30.0,
+            },
+            Shape::Ellipse {
+                x: 10.0,
+                y: 10.0,
+                width: 60.0,
+                height: 20.0,
+            },
+            Shape::Polygon(vec![pt2(0.0, 0.0), pt2(30.0, 0.0), pt2(15.0, 25.0)]),
+        ];
+        let batch = RoughGenerator::new(defaults.clone()).generate_all(&shapes);
+
+        let mut generator = RoughGenerator::new(defaults);
+        let sequential = vec![
+            generator.line(0.0, 0.0, 50.0, 20.0, None),
+            generator.rectangle(0.0, 0.0, 40.0, 30.0, None),
+            generator.ellipse(10.0, 10.0, 60.0, 20.0, None),
+            generator.polygon(vec![pt2(0.0, 0.0), pt2(30.0, 0.0), pt2(15.0, 25.0)], None),
+        ];
+
+        assert_eq!(batch.len(), sequential.len());
+        for (batched, sequential) in batch.into_iter().zip(sequential) {
+            assert_eq!(sets(batched), sets(sequential));
+        }
     }
 }
@@ -1,8 +1,21 @@
+pub mod backend;
+pub mod batch;
+pub mod convert;
 pub mod core;
+pub mod draw;
+pub mod error;
 pub mod filler;
 pub mod generator;
 pub mod geometry;
+#[cfg(feature = "pdf")]
+pub mod pdf;
+pub mod preset;
+pub mod random;
+#[cfg(feature = "raster")]
+pub mod raster;
 pub mod renderer;
+pub mod svg;
+pub mod transform;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,258 @@
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::backend::Paint;
+use crate::core::{Color, Drawable, LineCap, LineJoin, Op};
+use crate::svg::format_number;
+
+/// A multi-page vector PDF built from drawables, written without any dependencies.
+///
+/// Every page is `width` by `height` points and uses nannou's window coordinates:
+/// the origin is at the center of the page and y points up. Colors are written
+/// opaque; their alpha is ignored.
+#[derive(Clone)]
+pub struct PdfDocument {
+    width: f32,
+    height: f32,
+    pages: Vec<Vec<Drawable>>,
+}
+
+fn number(value: f32) -> String {
+    format_number(value, Some(3))
+}
+
+fn color(color: Color) -> String {
+    format!(
+        "{} {} {}",
+        number(color.red),
+        number(color.green),
+        number(color.blue)
+    )
+}
+
+fn write_ops(content: &mut String, ops: &[Op]) {
+    for item in ops.iter() {
+        match *item {
+            Op::Move(to) => writeln!(content, "{} {} m", number(to.x), number(to.y)),
+            Op::Line(to) => writeln!(content, "{} {} l", number(to.x), number(to.y)),
+            Op::Cubic(c1, c2, to) => writeln!(
+                content,
+                "{} {} {} {} {} {} c",
+                number(c1.x),
+                number(c1.y),
+                number(c2.x),
+                number(c2.y),
+                number(to.x),
+                number(to.y)
+            ),
+        }
+        .unwrap();
+    }
+}
+
+/// The content stream for one drawable, wrapped in `q`/`Q` so its graphics state
+/// does not leak into the next one.
+fn drawable_content(content: &mut String, drawable: &Drawable) {
+    let o = &drawable.options;
+    let cap = match o.line_cap {
+        LineCap::Butt => 0,
+        LineCap::Round => 1,
+        LineCap::Square => 2,
+    };
+    let join = match o.line_join {
+        LineJoin::Miter | LineJoin::MiterClip => 0,
+        LineJoin::Round => 1,
+        LineJoin::Bevel => 2,
+    };
+    content.push_str("q\n");
+    writeln!(content, "{} J {} j {} M", cap, join, number(o.miter_limit)).unwrap();
+    for set in drawable.sets.iter() {
+        // Painting without a current path is invalid in a content stream.
+        if set.ops.is_empty() {
+            continue;
+        }
+        match Paint::of(set.ops_type, o) {
+            Paint::Stroke { color: c, weight } => {
+                writeln!(content, "{} RG {} w", color(c), number(weight)).unwrap();
+                write_ops(content, &set.ops);
+                content.push_str("S\n");
+            }
+            Paint::Fill { color: c } => {
+                writeln!(content, "{} rg", color(c)).unwrap();
+                write_ops(content, &set.ops);
+                content.push_str("f\n");
+            }
+        }
+    }
+    content.push_str("Q\n");
+}
+
+impl PdfDocument {
+    /// An empty document with `width` by `height` point pages.
+    pub fn new(width: f32, height: f32) -> Self {
+        return PdfDocument {
+            width,
+            height,
+            pages: vec![],
+        };
+    }
+
+    /// Starts a new page; drawables added afterwards go on it.
+    pub fn add_page(&mut self) -> &mut Self {
+        self.pages.push(vec![]);
+        self
+    }
+
+    /// Adds `drawable` to the last page, starting the first page if there is none.
+    pub fn add(&mut self, drawable: Drawable) -> &mut Self {
+        if self.pages.is_empty() {
+            self.add_page();
+        }
+        self.pages.last_mut().unwrap().push(drawable);
+        self
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len().max(1)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let empty = vec![vec![]];
+        let pages = if self.pages.is_empty() {
+            &empty
+        } else {
+            &self.pages
+        };
+
+        // Object 1 is the catalog, 2 the page tree, then a page and its content
+        // stream for every page.
+        let mut objects: Vec<String> = vec![];
+        objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+        let kids = Vec::from_iter((0..pages.len()).map(|i| format!("{} 0 R", 3 + 2 * i)));
+        objects.push(format!(
+            "<< /Type /Pages /Kids [{}] /Count {} /MediaBox [0 0 {} {}] >>",
+            kids.join(" "),
+            pages.len(),
+            number(self.width),
+            number(self.height)
+        ));
+        for (i, page) in pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /Resources << >> /Contents {} 0 R >>",
+                4 + 2 * i
+            ));
+            let mut content = format!(
+                "1 0 0 1 {} {} cm\n",
+                number(self.width / 2.0),
+                number(self.height / 2.0)
+            );
+            for drawable in page.iter() {
+                drawable_content(&mut content, drawable);
+            }
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                content.len(),
+                content
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = vec![];
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            writeln!(pdf, "{} 0 obj\n{}\nendobj", i + 1, object).unwrap();
+        }
+        let xref = pdf.len();
+        write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).unwrap();
+        for offset in offsets.iter() {
+            writeln!(pdf, "{:010} 00000 n ", offset).unwrap();
+        }
+        write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .unwrap();
+        return pdf.into_bytes();
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{OpSet, OpSetType, Options};
+    use crate::generator;
+
+    fn document() -> PdfDocument {
+        let options = Options {
+            seed: 7,
+            fill: true,
+            ..Options::default()
+        };
+        let mut document = PdfDocument::new(200.0, 100.0);
+        document.add(generator::rectangle(-20.0, -10.0, 40.0, 20.0, options.clone()).unwrap());
+        document.add_page();
+        document.add(generator::ellipse(0.0, 0.0, 60.0, 30.0, options).unwrap());
+        document
+    }
+
+    #[test]
+    fn xref_offsets_point_at_objects() {
+        let bytes = document().to_bytes();
+        let pdf = std::str::from_utf8(&bytes).unwrap();
+        let tail = &pdf[pdf.rfind("startxref\n").unwrap() + "startxref\n".len()..];
+        let xref: usize = tail.lines().next().unwrap().parse().unwrap();
+        let mut lines = pdf[xref..].lines();
+        assert_eq!(lines.next(), Some("xref"));
+        let size: usize = lines.next().unwrap()["0 ".len()..].parse().unwrap();
+        assert_eq!(size, 2 + 2 * 2 + 1);
+        assert_eq!(lines.next(), Some("0000000000 65535 f "));
+        for object in 1..size {
+            let entry = lines.next().unwrap();
+            assert_eq!(entry.len(), 19);
+            assert!(entry.ends_with(" 00000 n "));
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", object)));
+        }
+        assert_eq!(lines.next(), Some("trailer"));
+    }
+
+    #[test]
+    fn stream_lengths_match_contents() {
+        let bytes = document().to_bytes();
+        let pdf = std::str::from_utf8(&bytes).unwrap();
+        let mut streams = 0;
+        let mut rest = pdf;
+        while let Some(start) = rest.find("/Length ") {
+            rest = &rest[start + "/Length ".len()..];
+            let end = rest.find(' ').unwrap();
+            let length: usize = rest[..end].parse().unwrap();
+            rest = &rest[rest.find("stream\n").unwrap() + "stream\n".len()..];
+            assert!(rest[length..].starts_with("endstream\n"));
+            assert!(rest[..length].ends_with("Q\n"));
+            streams += 1;
+        }
+        assert_eq!(streams, 2);
+    }
+
+    #[test]
+    fn empty_op_sets_are_skipped() {
+        let mut drawable = generator::line(0.0, 0.0, 10.0, 10.0, Options::default()).unwrap();
+        drawable.sets[0].ops.clear();
+        drawable.sets.push(OpSet {
+            ops_type: OpSetType::FillPath,
+            ops: vec![],
+        });
+        let mut content = String::new();
+        drawable_content(&mut content, &drawable);
+        assert!(!content.lines().any(|line| line == "S" || line == "f"));
+        assert!(content.starts_with("q\n") && content.ends_with("Q\n"));
+    }
+}
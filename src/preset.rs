@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use crate::core::{LineCap, LineJoin, Options, PartialOptions};
+use crate::error::{Error, Result};
+
+type PresetFn = fn() -> Options;
+
+const BUILTIN_PRESETS: [(&str, PresetFn); 6] = [
+    ("default", Options::new),
+    ("architect", Options::architect),
+    ("artist", Options::artist),
+    ("cartoonist", Options::cartoonist),
+    ("blueprint", Options::blueprint),
+    ("pencil", Options::pencil),
+];
+
+/// Built-in looks, tuned from the defaults.
+impl Options {
+    /// Low roughness and exact corners, for tidy technical drawings.
+    pub fn architect() -> Self {
+        Options {
+            roughness: 0.5,
+            bowing: 0.5,
+            max_randomness_offset: 1.5,
+            preserve_vertices: true,
+            stroke_width: 1.5,
+            fill_weight: 1.5,
+            hachure_gap: 8.0,
+            ..Options::new()
+        }
+    }
+
+    /// Loose strokes with dense, heavy hachures.
+    pub fn artist() -> Self {
+        Options {
+            roughness: 1.8,
+            bowing: 2.0,
+            hachure_angle: -41.0,
+            hachure_gap: 6.0,
+            fill_weight: 2.0,
+            ..Options::new()
+        }
+    }
+
+    /// Thick, bold outlines with wide hachures.
+    pub fn cartoonist() -> Self {
+        Options {
+            roughness: 1.2,
+            bowing: 1.0,
+            stroke_width: 4.0,
+            fill_weight: 3.5,
+            hachure_gap: 12.0,
+            curve_fitting: 0.9,
+            line_cap: LineCap::Round,
+            line_join: LineJoin::Round,
+            ..Options::new()
+        }
+    }
+
+    /// Thin single strokes that barely wobble.
+    pub fn blueprint() -> Self {
+        Options {
+            roughness: 0.3,
+            bowing: 0.2,
+            max_randomness_offset: 1.0,
+            disable_multi_stroke: true,
+            disable_multi_stroke_fill: true,
+            preserve_vertices: true,
+            hachure_angle: 60.0,
+            hachure_gap: 8.0,
+            stroke_width: 1.0,
+            fill_weight: 1.0,
+            ..Options::new()
+        }
+    }
+
+    /// Fine lines with tight, light shading.
+    pub fn pencil() -> Self {
+        Options {
+            roughness: 1.0,
+            bowing: 0.8,
+            max_randomness_offset: 2.0,
+            stroke_width: 1.0,
+            fill_weight: 0.8,
+            hachure_gap: 4.0,
+            ..Options::new()
+        }
+    }
+
+    /// Looks up a built-in preset by name.
+    pub fn preset(name: &str) -> Result<Self> {
+        BUILTIN_PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, options)| options())
+            .ok_or_else(|| Error::UnknownPreset(name.to_string()))
+    }
+}
+
+/// Named presets, starting with the built-in ones, that projects can extend.
+///
+/// A config file can then name a preset and list overrides on top of it, which
+/// `resolve` turns into `Options`.
+#[derive(Clone)]
+pub struct PresetRegistry {
+    presets: HashMap<String, Options>,
+}
+
+impl PresetRegistry {
+    pub fn new() -> Self {
+        PresetRegistry {
+            presets: HashMap::from_iter(
+                BUILTIN_PRESETS
+                    .iter()
+                    .map(|(name, options)| (name.to_string(), options())),
+            ),
+        }
+    }
+
+    pub fn empty() -> Self {
+        PresetRegistry {
+            presets: HashMap::new(),
+        }
+    }
+
+    /// Adds a preset, replacing any existing one with the same name.
+    pub fn register(&mut self, name: &str, options: Options) -> Result<()> {
+        options.validate()?;
+        self.presets.insert(name.to_string(), options);
+        return Ok(());
+    }
+
+    pub fn get(&self, name: &str) -> Result<Options> {
+        self.presets
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownPreset(name.to_string()))
+    }
+
+    /// The preset called `name` with `overrides` merged on top.
+    pub fn resolve(&self, name: &str, overrides: &PartialOptions) -> Result<Options> {
+        let options = self.get(name)?.merge(overrides);
+        options.validate()?;
+        return Ok(options);
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.presets.keys().map(|name| name.as_str())
+    }
+}
+
+impl Default for PresetRegistry {
+    fn default() -> Self {
+        PresetRegistry::new()
+    }
+}
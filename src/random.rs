@@ -0,0 +1,45 @@
+use std::cell::RefCell;
+
+use nannou::prelude::*;
+
+/// The Park-Miller generator rough.js uses for seeded drawings.
+#[derive(Copy, Clone, Debug)]
+pub struct Random {
+    seed: i32,
+}
+
+impl Random {
+    pub fn new(seed: u32) -> Self {
+        Random { seed: seed as i32 }
+    }
+
+    pub fn next_f32(&mut self) -> f32 {
+        self.seed = self.seed.wrapping_mul(48271);
+        ((self.seed & 0x7fff_ffff) as f64 / 2_f64.powi(31)) as f32
+    }
+}
+
+thread_local! {
+    static RANDOMIZER: RefCell<Option<Random>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with every random number drawn from a generator seeded with `seed`.
+/// A seed of 0 keeps nannou's unseeded randomness, as in rough.js.
+pub fn with_seed<T>(seed: u32, f: impl FnOnce() -> T) -> T {
+    let randomizer = if seed == 0 {
+        None
+    } else {
+        Some(Random::new(seed))
+    };
+    let previous = RANDOMIZER.with(|r| r.replace(randomizer));
+    let result = f();
+    RANDOMIZER.with(|r| r.replace(previous));
+    result
+}
+
+pub fn random() -> f32 {
+    RANDOMIZER.with(|r| match r.borrow_mut().as_mut() {
+        Some(randomizer) => randomizer.next_f32(),
+        None => random_f32(),
+    })
+}
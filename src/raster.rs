@@ -0,0 +1,187 @@
+use std::io;
+use std::mem;
+use std::path::Path;
+
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+use nannou::prelude::*;
+
+use crate::backend::{PathSink, RoughBackend};
+use crate::core::{Color, Drawable, LineCap, LineJoin, Options};
+
+/// A CPU canvas that renders drawables without a GPU.
+///
+/// Coordinates follow nannou's window: the origin is at the center and y points up,
+/// so a scene drawn with `Drawable::draw` into a `width` by `height` window lands
+/// on the same pixels here.
+pub struct Raster {
+    pixmap: Pixmap,
+    transform: Transform,
+    builder: PathBuilder,
+}
+
+impl From<LineCap> for tiny_skia::LineCap {
+    fn from(cap: LineCap) -> Self {
+        match cap {
+            LineCap::Butt => tiny_skia::LineCap::Butt,
+            LineCap::Square => tiny_skia::LineCap::Square,
+            LineCap::Round => tiny_skia::LineCap::Round,
+        }
+    }
+}
+
+impl From<LineJoin> for tiny_skia::LineJoin {
+    fn from(join: LineJoin) -> Self {
+        match join {
+            LineJoin::Miter => tiny_skia::LineJoin::Miter,
+            LineJoin::MiterClip => tiny_skia::LineJoin::MiterClip,
+            LineJoin::Round => tiny_skia::LineJoin::Round,
+            LineJoin::Bevel => tiny_skia::LineJoin::Bevel,
+        }
+    }
+}
+
+fn paint(color: Color) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(
+        tiny_skia::Color::from_rgba(color.red, color.green, color.blue, color.alpha)
+            .unwrap_or(tiny_skia::Color::BLACK),
+    );
+    paint.anti_alias = true;
+    return paint;
+}
+
+impl Raster {
+    /// A transparent `width` by `height` canvas. Returns `None` if either side is zero.
+    pub fn new(width: u32, height: u32) -> Option<Self> {
+        let pixmap = Pixmap::new(width, height)?;
+        let transform =
+            Transform::from_row(1.0, 0.0, 0.0, -1.0, width as f32 / 2.0, height as f32 / 2.0);
+        return Some(Raster {
+            pixmap,
+            transform,
+            builder: PathBuilder::new(),
+        });
+    }
+
+    pub fn width(&self) -> u32 {
+        self.pixmap.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.pixmap.height()
+    }
+
+    pub fn background(&mut self, color: Color) {
+        self.pixmap.fill(
+            tiny_skia::Color::from_rgba(color.red, color.green, color.blue, color.alpha)
+                .unwrap_or(tiny_skia::Color::BLACK),
+        );
+    }
+
+    /// Renders `drawable` as described in `Drawable::render`.
+    pub fn draw(&mut self, drawable: &Drawable) {
+        drawable.render(self);
+    }
+
+    /// The canvas as straight-alpha RGBA bytes, row by row from the top.
+    pub fn rgba(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(self.pixmap.data().len());
+        for pixel in self.pixmap.pixels().iter() {
+            let c = pixel.demultiply();
+            data.extend([c.red(), c.green(), c.blue(), c.alpha()]);
+        }
+        return data;
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.pixmap.save_png(path).map_err(io::Error::other)
+    }
+
+    fn take_path(&mut self) -> Option<tiny_skia::Path> {
+        mem::take(&mut self.builder).finish()
+    }
+}
+
+impl PathSink for Raster {
+    fn move_to(&mut self, to: Point2) {
+        self.builder.move_to(to.x, to.y);
+    }
+
+    fn line_to(&mut self, to: Point2) {
+        self.builder.line_to(to.x, to.y);
+    }
+
+    fn cubic_to(&mut self, ctrl1: Point2, ctrl2: Point2, to: Point2) {
+        self.builder
+            .cubic_to(ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y);
+    }
+}
+
+impl RoughBackend for Raster {
+    fn stroke(&mut self, color: Color, weight: f32, options: &Options) {
+        let path = match self.take_path() {
+            Some(path) => path,
+            None => return,
+        };
+        let stroke = Stroke {
+            width: weight,
+            miter_limit: options.miter_limit,
+            line_cap: options.line_cap.into(),
+            line_join: options.line_join.into(),
+            dash: None,
+        };
+        self.pixmap
+            .stroke_path(&path, &paint(color), &stroke, self.transform, None);
+    }
+
+    fn fill(&mut self, color: Color, _options: &Options) {
+        let path = match self.take_path() {
+            Some(path) => path,
+            None => return,
+        };
+        self.pixmap.fill_path(
+            &path,
+            &paint(color),
+            FillRule::Winding,
+            self.transform,
+            None,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FillStyle;
+    use crate::generator;
+
+    fn pixel(raster: &Raster, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * raster.width() + x) * 4) as usize;
+        let data = raster.rgba();
+        return [data[i], data[i + 1], data[i + 2], data[i + 3]];
+    }
+
+    #[test]
+    fn rectangles_land_on_nannou_coordinates() {
+        let options = Options {
+            roughness: 0.0,
+            fill: true,
+            fill_style: FillStyle::Solid,
+            fill_color: Color::rgb(1.0, 0.0, 0.0),
+            ..Options::default()
+        };
+        // Spans x 10..40 and y 10..30 in nannou's centered, y-up coordinates.
+        let rectangle = generator::rectangle(10.0, 10.0, 30.0, 20.0, options).unwrap();
+        let mut raster = Raster::new(100, 100).unwrap();
+        raster.draw(&rectangle);
+
+        // nannou (25, 20) is pixel (75, 30), counted from the top left.
+        assert_eq!(pixel(&raster, 75, 30), [255, 0, 0, 255]);
+        // Its mirror images across either axis stay empty.
+        assert_eq!(pixel(&raster, 75, 70)[3], 0);
+        assert_eq!(pixel(&raster, 25, 30)[3], 0);
+        assert_eq!(pixel(&raster, 50, 50)[3], 0);
+        assert_eq!(pixel(&raster, 0, 0)[3], 0);
+    }
+}
@@ -1,14 +1,21 @@
+use nannou::lyon::path::PathEvent;
 use nannou::prelude::*;
 
-use crate::core::{FillStyle, Op, OpSet, OpSetType, OpType, Options};
+use crate::core::{Op, OpSet, OpSetType, Options};
+use crate::error::{Error, Result};
 use crate::filler::get_filler;
+use crate::random::random;
 use OpSetType::*;
-use OpType::*;
 
 pub fn line(x1: f32, y1: f32, x2: f32, y2: f32, o: &Options) -> OpSet {
+    let ops = if o.stroke_line_dash.is_empty() {
+        _double_line(x1, y1, x2, y2, o)
+    } else {
+        _dashed_polyline(&[pt2(x1, y1), pt2(x2, y2)], o)
+    };
     OpSet {
         ops_type: Path,
-        ops: _double_line(x1, y1, x2, y2, o),
+        ops,
     }
 }
 
@@ -31,21 +38,15 @@ pub fn solid_fill_polygon(polygon_list: Vec<Vec<Point2>>, options: &Options) ->
     for points in polygon_list.iter() {
         if points.len() > 2 {
             let offset = options.max_randomness_offset;
-            ops.push(Op {
-                op: Move,
-                data: vec![
-                    points[0].x + _offset_opt(offset, options, 1.0),
-                    points[0].y + _offset_opt(offset, options, 1.0),
-                ],
-            });
+            ops.push(Op::Move(pt2(
+                points[0].x + _offset_opt(offset, options, 1.0),
+                points[0].y + _offset_opt(offset, options, 1.0),
+            )));
             for i in 1..points.len() {
-                ops.push(Op {
-                    op: LineTo,
-                    data: vec![
-                        points[i].x + _offset_opt(offset, options, 1.0),
-                        points[i].y + _offset_opt(offset, options, 1.0),
-                    ],
-                })
+                ops.push(Op::Line(pt2(
+                    points[i].x + _offset_opt(offset, options, 1.0),
+                    points[i].y + _offset_opt(offset, options, 1.0),
+                )))
             }
         }
     }
@@ -55,13 +56,22 @@ pub fn solid_fill_polygon(polygon_list: Vec<Vec<Point2>>, options: &Options) ->
     }
 }
 
-pub fn pattern_fill_polygon(polygon_list: Vec<Vec<Point2>>, options: &Options) -> OpSet {
-    return get_filler(options).fill_polygons(polygon_list, options);
+pub fn pattern_fill_polygon(polygon_list: Vec<Vec<Point2>>, options: &Options) -> Result<OpSet> {
+    return Ok(get_filler(options)?.fill_polygons(polygon_list, options));
 }
 
 pub fn linear_path(points: Vec<Point2>, close: bool, options: &Options) -> OpSet {
     let len = points.len();
-    if len > 2 {
+    if len > 2 && !options.stroke_line_dash.is_empty() {
+        let mut outline = points.clone();
+        if close {
+            outline.push(points[0]);
+        }
+        return OpSet {
+            ops_type: Path,
+            ops: _dashed_polyline(&outline, options),
+        };
+    } else if len > 2 {
         let mut ops = vec![];
         for i in 0..(len - 1) {
             ops.extend(_double_line(
@@ -104,7 +114,7 @@ pub fn arc(
     closed: bool,
     rough_closure: bool,
     options: &Options,
-) -> OpSet {
+) -> Result<OpSet> {
     let cx = x;
     let cy = y;
     let mut rx = (width / 2.0).abs();
@@ -123,10 +133,24 @@ pub fn arc(
     }
     let ellipse_inc = f32::PI() * 2.0 / options.curve_step_count as f32;
     let arc_inc = (ellipse_inc / 2.0).min((stop - start) / 2.0);
-    let mut ops = _arc(arc_inc, cx, cy, rx, ry, start, stop, 1.0, options);
-    if !options.disable_multi_stroke {
-        let ops2 = _arc(arc_inc, cx, cy, rx, ry, start, stop, 1.5, options);
-        ops.extend(ops2);
+    if !options.stroke_line_dash.is_empty() {
+        if arc_inc.is_nan() || arc_inc <= 0.0 {
+            return Err(Error::EmptyArc);
+        }
+        let mut outline = _ellipse_polyline(cx, cy, rx, ry, start, stop);
+        if closed {
+            outline.insert(0, pt2(cx, cy));
+            outline.push(pt2(cx, cy));
+        }
+        return Ok(OpSet {
+            ops_type: Path,
+            ops: _dashed_polyline(&outline, options),
+        });
+    }
+    let mut ops = vec![];
+    for pass in 0..options.stroke_passes() {
+        let offset = _curve_pass_offset(pass);
+        ops.extend(_arc(arc_inc, cx, cy, rx, ry, start, stop, offset, options)?);
     }
     if closed {
         if rough_closure {
@@ -145,20 +169,14 @@ pub fn arc(
                 options,
             ));
         } else {
-            ops.push(Op {
-                op: OpType::LineTo,
-                data: vec![cx, cy],
-            });
-            ops.push(Op {
-                op: OpType::LineTo,
-                data: vec![cx + rx * start.cos(), cy + ry * start.sin()],
-            });
+            ops.push(Op::Line(pt2(cx, cy)));
+            ops.push(Op::Line(pt2(cx + rx * start.cos(), cy + ry * start.sin())));
         }
     }
-    return OpSet {
+    return Ok(OpSet {
         ops_type: OpSetType::Path,
         ops,
-    };
+    });
 }
 
 fn _arc(
@@ -171,7 +189,10 @@ fn _arc(
     stop: f32,
     offset: f32,
     options: &Options,
-) -> Vec<Op> {
+) -> Result<Vec<Op>> {
+    if increment.is_nan() || increment <= 0.0 {
+        return Err(Error::EmptyArc);
+    }
     let rad_offset = start + _offset_opt(0.1, options, 1.0);
     let mut points: Vec<Point2> = vec![];
     points.push(pt2(
@@ -179,7 +200,6 @@ fn _arc(
         _offset_opt(offset, options, 1.0) + cy + 0.9 * ry * (rad_offset - increment).sin(),
     ));
     let mut angle = rad_offset;
-    assert!(increment > 0.0);
     while angle <= stop {
         points.push(pt2(
             _offset_opt(offset, options, 1.0) + cx + rx * angle.cos(),
@@ -189,7 +209,7 @@ fn _arc(
     }
     points.push(pt2(cx + rx * stop.cos(), cy + ry * stop.sin()));
     points.push(pt2(cx + rx * stop.cos(), cy + ry * stop.sin()));
-    return _curve(points, None, options);
+    return Ok(_curve(points, None, options));
 }
 
 pub fn pattern_fill_arc(
@@ -200,7 +220,7 @@ pub fn pattern_fill_arc(
     start: f32,
     stop: f32,
     options: &Options,
-) -> OpSet {
+) -> Result<OpSet> {
     let cx = x;
     let cy = y;
     let mut rx = (width / 2.0).abs();
@@ -218,6 +238,9 @@ pub fn pattern_fill_arc(
         stop = f32::PI();
     }
     let increment = (stop - start) / options.curve_step_count as f32;
+    if increment.is_nan() || increment <= 0.0 {
+        return Err(Error::EmptyArc);
+    }
     let mut points: Vec<Point2> = vec![];
     let mut angle = start;
     while angle <= stop {
@@ -229,6 +252,93 @@ pub fn pattern_fill_arc(
     return pattern_fill_polygon(vec![points], options);
 }
 
+#[derive(Clone)]
+pub struct PathResult {
+    pub opset: OpSet,
+    pub closed_polygons: Vec<Vec<Point2>>,
+}
+
+pub fn path<I>(events: I, options: &Options) -> PathResult
+where
+    I: IntoIterator<Item = PathEvent>,
+{
+    let mut ops: Vec<Op> = vec![];
+    let mut closed_polygons: Vec<Vec<Point2>> = vec![];
+    let mut points: Vec<Point2> = vec![];
+    for event in events {
+        match event {
+            PathEvent::Begin { at } => {
+                points = vec![pt2(at.x, at.y)];
+            }
+            PathEvent::Line { from, to } => {
+                ops.extend(_double_line(from.x, from.y, to.x, to.y, options));
+                points.push(pt2(to.x, to.y));
+            }
+            PathEvent::Quadratic { from, ctrl, to } => {
+                let start = pt2(from.x, from.y);
+                let control = pt2(ctrl.x, ctrl.y);
+                let end = pt2(to.x, to.y);
+                let (control1, control2) = _elevate_quadratic(start, control, end);
+                ops.extend(_double_bezier(start, control1, control2, end, options));
+                points.extend(_sample_bezier(start, control1, control2, end, options));
+            }
+            PathEvent::Cubic {
+                from,
+                ctrl1,
+                ctrl2,
+                to,
+            } => {
+                let start = pt2(from.x, from.y);
+                let control1 = pt2(ctrl1.x, ctrl1.y);
+                let control2 = pt2(ctrl2.x, ctrl2.y);
+                let end = pt2(to.x, to.y);
+                ops.extend(_double_bezier(start, control1, control2, end, options));
+                points.extend(_sample_bezier(start, control1, control2, end, options));
+            }
+            PathEvent::End { last, first, close } => {
+                if close {
+                    if last != first {
+                        ops.extend(_double_line(last.x, last.y, first.x, first.y, options));
+                    }
+                    if points.len() > 2 {
+                        closed_polygons.push(points.clone());
+                    }
+                }
+                points.clear();
+            }
+        }
+    }
+    return PathResult {
+        opset: OpSet {
+            ops_type: Path,
+            ops,
+        },
+        closed_polygons,
+    };
+}
+
+fn _sample_bezier(
+    start: Point2,
+    control1: Point2,
+    control2: Point2,
+    end: Point2,
+    options: &Options,
+) -> Vec<Point2> {
+    let step_count = options.curve_step_count.max(2);
+    return Vec::from_iter(
+        (1..=step_count)
+            .map(|i| _bezier_point(start, control1, control2, end, i as f32 / step_count as f32)),
+    );
+}
+
+fn _bezier_point(start: Point2, control1: Point2, control2: Point2, end: Point2, t: f32) -> Point2 {
+    let mt = 1.0 - t;
+    return start * (mt * mt * mt)
+        + control1 * (3.0 * mt * mt * t)
+        + control2 * (3.0 * mt * t * t)
+        + end * (t * t * t);
+}
+
 #[derive(Clone)]
 pub struct EllipseResult {
     pub opset: OpSet,
@@ -246,7 +356,7 @@ pub fn generate_ellipse_params(width: f32, height: f32, options: &Options) -> El
     let psq =
         (f32::PI() * 2.0 * (((width * 0.5).powi(2) + (height * 0.5).powi(2)) * 0.5).sqrt()).sqrt();
     let step_count = (options.curve_step_count as f32)
-        .max(psq * options.curve_step_count as f32 / 200.0.sqrt())
+        .max(psq * options.curve_step_count as f32 / 200.0_f32.sqrt())
         .ceil();
     let increment = 2.0 * f32::PI() / step_count;
     let mut rx = (width / 2.0).abs();
@@ -275,27 +385,45 @@ pub fn ellipse_with_params(
         overlap,
         options,
     );
-    let mut o1 = _curve(ap1, None, options);
-    if !options.disable_multi_stroke && (options.roughness != 0.0) {
-        let (ap2, _) = _compute_ellipse_points(
-            ellipse_params.increment,
+    if !options.stroke_line_dash.is_empty() {
+        let outline = _ellipse_polyline(
             x,
             y,
             ellipse_params.rx,
             ellipse_params.ry,
-            1.5,
             0.0,
-            options,
+            f32::PI() * 2.0,
         );
-        o1.extend(_curve(ap2, None, options));
+        return EllipseResult {
+            estimated_points: cp1,
+            opset: OpSet {
+                ops_type: Path,
+                ops: _dashed_polyline(&outline, options),
+            },
+        };
+    }
+    let mut o1 = _curve(ap1, None, options);
+    if options.roughness != 0.0 {
+        for pass in 1..options.stroke_passes() {
+            let (ap2, _) = _compute_ellipse_points(
+                ellipse_params.increment,
+                x,
+                y,
+                ellipse_params.rx,
+                ellipse_params.ry,
+                _curve_pass_offset(pass),
+                0.0,
+                options,
+            );
+            o1.extend(_curve(ap2, None, options));
+        }
     }
-    let ops_type = match options.fill_style {
-        FillStyle::Solid => OpSetType::FillPath,
-        _ => OpSetType::Path,
-    };
     return EllipseResult {
         estimated_points: cp1,
-        opset: OpSet { ops_type, ops: o1 },
+        opset: OpSet {
+            ops_type: Path,
+            ops: o1,
+        },
     };
 }
 
@@ -367,50 +495,31 @@ fn _curve(points: Vec<Point2>, close_point: Option<Point2>, options: &Options) -
     let mut ops: Vec<Op> = vec![];
     if len > 3 {
         let s = 1.0 - options.curve_tightness;
-        ops.push(Op {
-            op: OpType::Move,
-            data: vec![points[1].x, points[1].y],
-        });
+        ops.push(Op::Move(points[1]));
         for i in 1..(len - 2) {
             let cached_vert_array = points[i];
-            ops.push(Op {
-                op: OpType::BcurveTo,
-                data: vec![
+            ops.push(Op::Cubic(
+                pt2(
                     cached_vert_array.x + (s * points[i + 1].x - s * points[i - 1].x) / 6.0,
                     cached_vert_array.y + (s * points[i + 1].y - s * points[i - 1].y) / 6.0,
+                ),
+                pt2(
                     points[i + 1].x + (s * points[i].x - s * points[i + 2].x) / 6.0,
                     points[i + 1].y + (s * points[i].y - s * points[i + 2].y) / 6.0,
-                    points[i + 1].x,
-                    points[i + 1].y,
-                ],
-            });
+                ),
+                points[i + 1],
+            ));
         }
         if let Some(clp) = close_point {
             let ro = options.max_randomness_offset;
-            ops.push(Op {
-                op: OpType::LineTo,
-                data: vec![
-                    clp.x + _offset_opt(ro, options, 1.0),
-                    clp.y + _offset_opt(ro, options, 1.0),
-                ],
-            })
+            ops.push(Op::Line(pt2(
+                clp.x + _offset_opt(ro, options, 1.0),
+                clp.y + _offset_opt(ro, options, 1.0),
+            )))
         }
     } else if len == 3 {
-        ops.push(Op {
-            op: OpType::Move,
-            data: vec![points[1].x, points[1].y],
-        });
-        ops.push(Op {
-            op: OpType::BcurveTo,
-            data: vec![
-                points[1].x,
-                points[1].y,
-                points[2].x,
-                points[2].y,
-                points[2].x,
-                points[2].y,
-            ],
-        });
+        ops.push(Op::Move(points[1]));
+        ops.push(Op::Cubic(points[1], points[2], points[2]));
     } else if len == 2 {
         ops.extend(
             _double_line(points[0].x, points[0].y, points[1].x, points[1].y, options).into_iter(),
@@ -419,32 +528,232 @@ fn _curve(points: Vec<Point2>, close_point: Option<Point2>, options: &Options) -
     return ops;
 }
 
+pub fn quadratic_bezier(start: Point2, control: Point2, end: Point2, options: &Options) -> OpSet {
+    let (control1, control2) = _elevate_quadratic(start, control, end);
+    return cubic_bezier(start, control1, control2, end, options);
+}
+
+fn _elevate_quadratic(start: Point2, control: Point2, end: Point2) -> (Point2, Point2) {
+    (
+        start + (control - start) * (2.0 / 3.0),
+        end + (control - end) * (2.0 / 3.0),
+    )
+}
+
+pub fn cubic_bezier(
+    start: Point2,
+    control1: Point2,
+    control2: Point2,
+    end: Point2,
+    options: &Options,
+) -> OpSet {
+    return OpSet {
+        ops_type: Path,
+        ops: _double_bezier(start, control1, control2, end, options),
+    };
+}
+
+fn _double_bezier(
+    start: Point2,
+    control1: Point2,
+    control2: Point2,
+    end: Point2,
+    options: &Options,
+) -> Vec<Op> {
+    let mut ops = vec![];
+    for pass in 0..options.stroke_passes() {
+        let offset = _curve_pass_offset(pass);
+        ops.extend(_bezier(start, control1, control2, end, offset, options));
+    }
+    return ops;
+}
+
+fn _bezier(
+    start: Point2,
+    control1: Point2,
+    control2: Point2,
+    end: Point2,
+    offset: f32,
+    o: &Options,
+) -> Vec<Op> {
+    let length = start.distance(end);
+    let roughness_gain = _roughness_gain(length);
+    let mid_disp = pt2(
+        _offset_opt(
+            o.bowing * o.max_randomness_offset * (end.y - start.y) / 200.0,
+            o,
+            roughness_gain,
+        ),
+        _offset_opt(
+            o.bowing * o.max_randomness_offset * (start.x - end.x) / 200.0,
+            o,
+            roughness_gain,
+        ),
+    );
+    let pv = o.preserve_vertices;
+    let step_count = o.curve_step_count.max(2);
+    let mut points: Vec<Point2> = vec![];
+    for i in 0..=step_count {
+        let t = i as f32 / step_count as f32;
+        let p = _bezier_point(start, control1, control2, end, t);
+        let is_vertex = i == 0 || i == step_count;
+        let jitter = if pv && is_vertex {
+            pt2(0.0, 0.0)
+        } else {
+            pt2(
+                _offset_opt(offset, o, roughness_gain),
+                _offset_opt(offset, o, roughness_gain),
+            )
+        };
+        points.push(p + mid_disp * (f32::PI() * t).sin() + jitter);
+    }
+    // _curve draws from the second to the second-to-last point, so the ends are doubled.
+    points.insert(0, points[0]);
+    points.push(points[points.len() - 1]);
+    return _curve(points, None, o);
+}
+
+/// Points along the elliptical arc from `start` to `stop`, close enough together that
+/// the polyline through them follows the curve.
+fn _ellipse_polyline(cx: f32, cy: f32, rx: f32, ry: f32, start: f32, stop: f32) -> Vec<Point2> {
+    let arc_length = (stop - start) * (rx + ry) * 0.5;
+    let step_count = ((arc_length / 10.0).ceil() as usize).max(8);
+    return Vec::from_iter((0..=step_count).map(|i| {
+        let angle = start + (stop - start) * i as f32 / step_count as f32;
+        pt2(cx + rx * angle.cos(), cy + ry * angle.sin())
+    }));
+}
+
+/// Breaks the polyline through `points` into rough dashes following
+/// `stroke_line_dash`. The dash phase carries over from one segment to the next, so
+/// dashes continue around corners.
+fn _dashed_polyline(points: &[Point2], o: &Options) -> Vec<Op> {
+    return _dashed_lines(points, &o.stroke_line_dash, o.stroke_line_dash_offset, o);
+}
+
+/// Segments that would take more dash periods than this are drawn solid: the dashes
+/// could not be told apart, and the walk along the segment would stall.
+const MAX_DASH_PERIODS: f32 = 10_000.0;
+
+/// Breaks the polyline through `points` into rough dashes. Zero-length dashes are drawn
+/// as short dots one stroke width long, so `[0.0, gap]` gives a dotted line.
+pub fn _dashed_lines(points: &[Point2], dash: &[f32], dash_offset: f32, o: &Options) -> Vec<Op> {
+    // As in SVG, an odd number of lengths is repeated to get an even pattern.
+    let pattern = if dash.len() % 2 == 1 {
+        [dash, dash].concat()
+    } else {
+        dash.to_vec()
+    };
+    let total: f32 = pattern.iter().sum();
+    if pattern.is_empty() || !(total.is_finite() && total > 0.0) {
+        return Vec::from_iter(
+            points
+                .windows(2)
+                .flat_map(|p| _double_line(p[0].x, p[0].y, p[1].x, p[1].y, o)),
+        );
+    }
+
+    let (mut index, mut remaining) = _advance_dash(&pattern, 0, pattern[0], dash_offset);
+    let mut ops: Vec<Op> = vec![];
+    for segment in points.windows(2) {
+        let (start, end) = (segment[0], segment[1]);
+        let length = start.distance(end);
+        if length / total > MAX_DASH_PERIODS {
+            ops.extend(_double_line(start.x, start.y, end.x, end.y, o));
+            (index, remaining) = _advance_dash(&pattern, index, remaining, length);
+            continue;
+        }
+        let mut position = 0.0;
+        while position < length {
+            let step = remaining.min(length - position);
+            let next = if step == length - position {
+                length
+            } else if step > 0.0 {
+                // Always move forward, even when the step is below f32 resolution here.
+                // `position` is never negative, so the next float up is one bit more.
+                (position + step).max(f32::from_bits(position.to_bits() + 1))
+            } else {
+                position
+            };
+            if index % 2 == 0 {
+                let a = start.lerp(end, position / length);
+                if step > 0.0 {
+                    let b = start.lerp(end, next / length);
+                    ops.extend(_double_line(a.x, a.y, b.x, b.y, o));
+                } else {
+                    let half = (end - start) / length * o.stroke_width.max(1.0) * 0.5;
+                    let (from, to) = (a - half, a + half);
+                    ops.extend(_double_line(from.x, from.y, to.x, to.y, o));
+                }
+            }
+            position = next;
+            remaining -= step;
+            if remaining <= 0.0 {
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+            }
+        }
+    }
+    return ops;
+}
+
+/// Walks `distance` along `pattern` from the `remaining` part of dash `index`, returning
+/// the dash it ends in and how much of that dash is left.
+fn _advance_dash(pattern: &[f32], index: usize, remaining: f32, distance: f32) -> (usize, f32) {
+    let total: f32 = pattern.iter().sum();
+    let mut index = index;
+    let mut remaining = remaining;
+    let mut phase = distance.rem_euclid(total);
+    while phase > 0.0 {
+        if phase < remaining {
+            remaining -= phase;
+            break;
+        }
+        phase -= remaining;
+        index = (index + 1) % pattern.len();
+        remaining = pattern[index];
+    }
+    return (index, remaining);
+}
+
+/// Offset of the random jitter for the `pass`-th stroke along a curve: 1.0 for the first
+/// stroke, then growing by 0.5 per extra stroke.
+fn _curve_pass_offset(pass: usize) -> f32 {
+    1.0 + 0.5 * pass as f32
+}
+
+/// Draws `options.stroke_passes()` overlapping rough strokes of the line. The first
+/// stroke takes the full offsets, later ones the half-offset overlay, spreading a little
+/// more with every extra pass.
 pub fn _double_line(x1: f32, y1: f32, x2: f32, y2: f32, o: &Options) -> Vec<Op> {
-    let single_stroke = o.disable_multi_stroke;
-    let o1 = _line(x1, y1, x2, y2, o, true, false);
-    if single_stroke {
-        return o1;
+    let mut ops = _line(x1, y1, x2, y2, o, true, false, 1.0);
+    for pass in 1..o.stroke_passes() {
+        let offset_scale = 1.0 + 0.5 * (pass - 1) as f32;
+        ops.extend(_line(x1, y1, x2, y2, o, true, true, offset_scale));
     }
-    let o2 = _line(x1, y1, x2, y2, o, true, true);
-    return vec![o1, o2].concat();
+    return ops;
 }
 
-fn _line(x1: f32, y1: f32, x2: f32, y2: f32, o: &Options, ismove: bool, overlay: bool) -> Vec<Op> {
+fn _line(
+    x1: f32,
+    y1: f32,
+    x2: f32,
+    y2: f32,
+    o: &Options,
+    ismove: bool,
+    overlay: bool,
+    offset_scale: f32,
+) -> Vec<Op> {
     let length_sq = pt2(x1, y1).distance_squared(pt2(x2, y2));
     let length = length_sq.sqrt();
-    let roughness_gain = if length < 200.0 {
-        1.0
-    } else if length > 500.0 {
-        0.4
-    } else {
-        (-0.0016668) * length + 1.233334
-    };
+    let roughness_gain = _roughness_gain(length);
     let mut offset = o.max_randomness_offset;
     if (offset.powi(2) * 100.0) > length_sq {
         offset = length / 100.0;
     }
+    offset *= offset_scale;
     let half_offset = offset * 0.5;
-    let diverge_point = random_range::<f32>(0.2, 0.4);
+    let diverge_point = 0.2 + random() * 0.2;
     let mut mid_disp_x = o.bowing * o.max_randomness_offset * (y2 - y1) / 200.0;
     mid_disp_x = _offset_opt(mid_disp_x, o, roughness_gain);
     let mut mid_disp_y = o.bowing * o.max_randomness_offset * (x1 - x2) / 200.0;
@@ -457,55 +766,197 @@ fn _line(x1: f32, y1: f32, x2: f32, y2: f32, o: &Options, ismove: bool, overlay:
     let mut ops: Vec<Op> = vec![];
     if ismove {
         if overlay {
-            ops.push(Op {
-                op: Move,
-                data: vec![
-                    x1 + if pv { 0.0 } else { random_half() },
-                    y1 + if pv { 0.0 } else { random_half() },
-                ],
-            });
+            ops.push(Op::Move(pt2(
+                x1 + if pv { 0.0 } else { random_half() },
+                y1 + if pv { 0.0 } else { random_half() },
+            )));
         } else {
-            ops.push(Op {
-                op: Move,
-                data: vec![
-                    x1 + if pv { 0.0 } else { random_full() },
-                    y1 + if pv { 0.0 } else { random_full() },
-                ],
-            });
+            ops.push(Op::Move(pt2(
+                x1 + if pv { 0.0 } else { random_full() },
+                y1 + if pv { 0.0 } else { random_full() },
+            )));
         }
     }
     if overlay {
-        ops.push(Op {
-            op: BcurveTo,
-            data: vec![
+        ops.push(Op::Cubic(
+            pt2(
                 mid_disp_x + x1 + (x2 - x1) * diverge_point + random_half(),
                 mid_disp_y + y1 + (y2 - y1) * diverge_point + random_half(),
+            ),
+            pt2(
                 mid_disp_x + x1 + 2.0 * (x2 - x1) * diverge_point + random_half(),
                 mid_disp_y + y1 + 2.0 * (y2 - y1) * diverge_point + random_half(),
+            ),
+            pt2(
                 x2 + if pv { 0.0 } else { random_half() },
                 y2 + if pv { 0.0 } else { random_half() },
-            ],
-        });
+            ),
+        ));
     } else {
-        ops.push(Op {
-            op: BcurveTo,
-            data: vec![
+        ops.push(Op::Cubic(
+            pt2(
                 mid_disp_x + x1 + (x2 - x1) * diverge_point + random_full(),
                 mid_disp_y + y1 + (y2 - y1) * diverge_point + random_full(),
+            ),
+            pt2(
                 mid_disp_x + x1 + 2.0 * (x2 - x1) * diverge_point + random_full(),
                 mid_disp_y + y1 + 2.0 * (y2 - y1) * diverge_point + random_full(),
+            ),
+            pt2(
                 x2 + if pv { 0.0 } else { random_full() },
                 y2 + if pv { 0.0 } else { random_full() },
-            ],
-        });
+            ),
+        ));
     }
     ops
 }
 
+fn _roughness_gain(length: f32) -> f32 {
+    if length < 200.0 {
+        1.0
+    } else if length > 500.0 {
+        0.4
+    } else {
+        (-0.0016668) * length + 1.233334
+    }
+}
+
 fn _offset(min: f32, max: f32, ops: &Options, roughness_gain: f32) -> f32 {
-    ops.roughness * roughness_gain * (random_f32() * (max - min) + min)
+    ops.roughness * roughness_gain * (random() * (max - min) + min)
 }
 
 fn _offset_opt(x: f32, ops: &Options, roughness_gain: f32) -> f32 {
     _offset(-x, x, ops, roughness_gain)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::with_seed;
+
+    fn smooth() -> Options {
+        return Options {
+            roughness: 0.0,
+            disable_multi_stroke: true,
+            ..Options::default()
+        };
+    }
+
+    /// The start and end of every dash drawn along `points`.
+    fn dashes(points: &[Point2], dash: &[f32], offset: f32, o: &Options) -> Vec<(Point2, Point2)> {
+        let ops = _dashed_lines(points, dash, offset, o);
+        return Vec::from_iter(ops.chunks(2).map(|pair| match pair {
+            [Op::Move(from), Op::Cubic(_, _, to)] => (*from, *to),
+            _ => panic!("expected a move and a curve per dash"),
+        }));
+    }
+
+    fn assert_dashes(found: Vec<(Point2, Point2)>, expected: &[[f32; 4]]) {
+        assert_eq!(found.len(), expected.len(), "dashes: {:?}", found);
+        for ((from, to), [x1, y1, x2, y2]) in found.iter().zip(expected) {
+            assert!(from.distance(pt2(*x1, *y1)) < 1e-4, "dashes: {:?}", found);
+            assert!(to.distance(pt2(*x2, *y2)) < 1e-4, "dashes: {:?}", found);
+        }
+    }
+
+    #[test]
+    fn dashes_follow_the_pattern() {
+        let line = [pt2(0.0, 0.0), pt2(10.0, 0.0)];
+        let found = dashes(&line, &[2.0, 3.0], 0.0, &smooth());
+        assert_dashes(found, &[[0.0, 0.0, 2.0, 0.0], [5.0, 0.0, 7.0, 0.0]]);
+    }
+
+    #[test]
+    fn dash_phase_carries_around_corners() {
+        let corner = [pt2(0.0, 0.0), pt2(2.0, 0.0), pt2(2.0, 5.0)];
+        let found = dashes(&corner, &[3.0, 2.0], 0.0, &smooth());
+        assert_dashes(
+            found,
+            &[
+                [0.0, 0.0, 2.0, 0.0],
+                [2.0, 0.0, 2.0, 1.0],
+                [2.0, 3.0, 2.0, 5.0],
+            ],
+        );
+
+        let corner = [pt2(0.0, 0.0), pt2(4.0, 0.0), pt2(4.0, 4.0)];
+        let found = dashes(&corner, &[3.0, 2.0], 0.0, &smooth());
+        assert_dashes(found, &[[0.0, 0.0, 3.0, 0.0], [4.0, 1.0, 4.0, 4.0]]);
+    }
+
+    #[test]
+    fn odd_patterns_repeat() {
+        let line = [pt2(0.0, 0.0), pt2(5.0, 0.0)];
+        let found = dashes(&line, &[1.0], 0.0, &smooth());
+        assert_dashes(
+            found,
+            &[
+                [0.0, 0.0, 1.0, 0.0],
+                [2.0, 0.0, 3.0, 0.0],
+                [4.0, 0.0, 5.0, 0.0],
+            ],
+        );
+
+        let line = [pt2(0.0, 0.0), pt2(12.0, 0.0)];
+        let found = dashes(&line, &[1.0, 2.0, 3.0], 0.0, &smooth());
+        assert_dashes(
+            found,
+            &[
+                [0.0, 0.0, 1.0, 0.0],
+                [3.0, 0.0, 6.0, 0.0],
+                [7.0, 0.0, 9.0, 0.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn dash_offset_shifts_the_pattern() {
+        let line = [pt2(0.0, 0.0), pt2(10.0, 0.0)];
+        let found = dashes(&line, &[2.0, 3.0], 1.0, &smooth());
+        assert_dashes(
+            found,
+            &[
+                [0.0, 0.0, 1.0, 0.0],
+                [4.0, 0.0, 6.0, 0.0],
+                [9.0, 0.0, 10.0, 0.0],
+            ],
+        );
+        let found = dashes(&line, &[2.0, 3.0], -1.0, &smooth());
+        assert_dashes(found, &[[1.0, 0.0, 3.0, 0.0], [6.0, 0.0, 8.0, 0.0]]);
+    }
+
+    #[test]
+    fn stroke_line_dash_offset_is_used() {
+        let options = Options {
+            stroke_line_dash: vec![2.0, 3.0],
+            stroke_line_dash_offset: 1.0,
+            ..smooth()
+        };
+        let line = [pt2(0.0, 0.0), pt2(10.0, 0.0)];
+        assert_eq!(
+            with_seed(1, || _dashed_polyline(&line, &options)),
+            with_seed(1, || _dashed_lines(&line, &[2.0, 3.0], 1.0, &options))
+        );
+    }
+
+    #[test]
+    fn zero_length_dashes_draw_dots() {
+        let line = [pt2(0.0, 0.0), pt2(4.0, 0.0)];
+        let options = Options {
+            stroke_width: 1.0,
+            ..smooth()
+        };
+        let found = dashes(&line, &[0.0, 2.0], 0.0, &options);
+        assert_dashes(found, &[[-0.5, 0.0, 0.5, 0.0], [1.5, 0.0, 2.5, 0.0]]);
+    }
+
+    #[test]
+    fn tiny_dashes_finish() {
+        let line = [pt2(0.0, 0.0), pt2(1000.0, 0.0)];
+        let found = dashes(&line, &[1e-5], 0.0, &smooth());
+        assert_dashes(found, &[[0.0, 0.0, 1000.0, 0.0]]);
+
+        let found = dashes(&line, &[1e-9, 1.0], 0.0, &smooth());
+        assert!(found.len() >= 999 && found.len() <= 1001);
+    }
+}
@@ -0,0 +1,341 @@
+use std::fmt::{self, Write};
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::Path;
+
+use nannou::geom::Rect;
+use nannou::prelude::*;
+
+use crate::backend::{PathSink, RoughBackend};
+use crate::core::{Color, Drawable, Op, Options};
+
+/// One op set of a `Drawable` as an SVG `<path>`: the `d` string plus the paint
+/// taken from the drawable's options. `None` paints are written as `none`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgPath {
+    pub d: String,
+    pub stroke: Option<Color>,
+    pub stroke_width: f32,
+    pub fill: Option<Color>,
+}
+
+impl SvgPath {
+    /// The path as a self-closing `<path/>` element.
+    pub fn to_element(&self) -> String {
+        let mut element = format!("<path d=\"{}\"", self.d);
+        match self.stroke {
+            Some(stroke) => {
+                write!(
+                    element,
+                    " stroke=\"{}\" stroke-width=\"{}\"",
+                    stroke.to_hex(),
+                    self.stroke_width
+                )
+                .unwrap();
+                if stroke.alpha < 1.0 {
+                    write!(element, " stroke-opacity=\"{}\"", stroke.alpha).unwrap();
+                }
+            }
+            None => element.push_str(" stroke=\"none\""),
+        }
+        match self.fill {
+            Some(fill) => {
+                write!(element, " fill=\"{}\"", fill.to_hex()).unwrap();
+                if fill.alpha < 1.0 {
+                    write!(element, " fill-opacity=\"{}\"", fill.alpha).unwrap();
+                }
+            }
+            None => element.push_str(" fill=\"none\""),
+        }
+        element.push_str("/>");
+        return element;
+    }
+}
+
+/// Formats `value` with at most `precision` decimal places, dropping trailing zeros.
+/// `None` keeps the shortest representation that round-trips.
+pub(crate) fn format_number(value: f32, precision: Option<usize>) -> String {
+    let text = match precision {
+        Some(precision) => {
+            let text = format!("{:.*}", precision, value);
+            if text.contains('.') {
+                text.trim_end_matches('0').trim_end_matches('.').to_string()
+            } else {
+                text
+            }
+        }
+        None => format!("{}", value),
+    };
+    if text == "-0" {
+        return "0".to_string();
+    }
+    return text;
+}
+
+/// Appends `command` and its coordinates to `d`, pairing the coordinates as points.
+fn push_command(d: &mut String, command: char, values: &[f32], precision: Option<usize>) {
+    if !d.is_empty() {
+        d.push(' ');
+    }
+    d.push(command);
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            d.push(if i % 2 == 0 { ',' } else { ' ' });
+        }
+        d.push_str(&format_number(*value, precision));
+    }
+}
+
+/// Converts ops to an SVG path `d` string, mapping `Move`, `Line` and `Cubic` to
+/// `M`, `L` and `C`.
+pub fn ops_to_path(ops: &[Op], precision: Option<usize>) -> String {
+    let mut d = String::new();
+    for item in ops.iter() {
+        match *item {
+            Op::Move(to) => push_command(&mut d, 'M', &[to.x, to.y], precision),
+            Op::Line(to) => push_command(&mut d, 'L', &[to.x, to.y], precision),
+            Op::Cubic(c1, c2, to) => {
+                let values = [c1.x, c1.y, c2.x, c2.y, to.x, to.y];
+                push_command(&mut d, 'C', &values, precision);
+            }
+        }
+    }
+    return d;
+}
+
+/// Collects what it renders as `SvgPath`s, one per stroke or fill.
+pub struct SvgBackend {
+    precision: Option<usize>,
+    d: String,
+    paths: Vec<SvgPath>,
+}
+
+impl SvgBackend {
+    pub fn new(precision: Option<usize>) -> Self {
+        return SvgBackend {
+            precision,
+            d: String::new(),
+            paths: vec![],
+        };
+    }
+
+    pub fn paths(&self) -> &[SvgPath] {
+        &self.paths
+    }
+
+    pub fn into_paths(self) -> Vec<SvgPath> {
+        self.paths
+    }
+}
+
+impl PathSink for SvgBackend {
+    fn move_to(&mut self, to: Point2) {
+        push_command(&mut self.d, 'M', &[to.x, to.y], self.precision);
+    }
+
+    fn line_to(&mut self, to: Point2) {
+        push_command(&mut self.d, 'L', &[to.x, to.y], self.precision);
+    }
+
+    fn cubic_to(&mut self, ctrl1: Point2, ctrl2: Point2, to: Point2) {
+        let values = [ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y];
+        push_command(&mut self.d, 'C', &values, self.precision);
+    }
+}
+
+impl RoughBackend for SvgBackend {
+    fn stroke(&mut self, color: Color, weight: f32, _options: &Options) {
+        self.paths.push(SvgPath {
+            d: mem::take(&mut self.d),
+            stroke: Some(color),
+            stroke_width: weight,
+            fill: None,
+        });
+    }
+
+    fn fill(&mut self, color: Color, _options: &Options) {
+        self.paths.push(SvgPath {
+            d: mem::take(&mut self.d),
+            stroke: None,
+            stroke_width: 0.0,
+            fill: Some(color),
+        });
+    }
+}
+
+impl Drawable {
+    /// One `SvgPath` per op set, like rough.js `toPaths`. Paint follows
+    /// `Drawable::render`.
+    pub fn to_svg_paths(&self, precision: Option<usize>) -> Vec<SvgPath> {
+        let mut backend = SvgBackend::new(precision);
+        self.render(&mut backend);
+        return backend.into_paths();
+    }
+}
+
+/// A standalone SVG file built from many drawables. Each drawable becomes a `<g>`
+/// holding its paths.
+///
+/// `SvgDocument::new` keeps coordinates as they are. `SvgDocument::from_rect` takes a
+/// nannou window rect and flips y, so a scene drawn with nannou's centered, y-up
+/// coordinates comes out the same way up in the SVG.
+#[derive(Clone)]
+pub struct SvgDocument {
+    view_box: [f32; 4],
+    flip_y: bool,
+    precision: Option<usize>,
+    background: Option<Color>,
+    drawables: Vec<Drawable>,
+}
+
+impl SvgDocument {
+    /// A `width` by `height` document with the origin at the top left.
+    pub fn new(width: f32, height: f32) -> Self {
+        return SvgDocument {
+            view_box: [0.0, 0.0, width, height],
+            flip_y: false,
+            precision: Some(2),
+            background: None,
+            drawables: vec![],
+        };
+    }
+
+    /// A document covering `rect` in nannou coordinates, flipped to SVG's y-down.
+    pub fn from_rect(rect: Rect) -> Self {
+        return SvgDocument {
+            view_box: [rect.left(), -rect.top(), rect.w(), rect.h()],
+            flip_y: true,
+            ..SvgDocument::new(rect.w(), rect.h())
+        };
+    }
+
+    pub fn flip_y(mut self, flip_y: bool) -> Self {
+        if flip_y != self.flip_y {
+            let [x, y, w, h] = self.view_box;
+            self.view_box = [x, -(y + h), w, h];
+            self.flip_y = flip_y;
+        }
+        self
+    }
+
+    /// Decimal places written for coordinates; `None` writes them in full.
+    pub fn precision(mut self, precision: Option<usize>) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn background(mut self, color: Color) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    pub fn add(&mut self, drawable: Drawable) -> &mut Self {
+        self.drawables.push(drawable);
+        self
+    }
+
+    pub fn drawables(&self) -> &[Drawable] {
+        &self.drawables
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for SvgDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x, y, w, h] = self.view_box;
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">",
+            w, h, x, y, w, h
+        )?;
+        if let Some(background) = self.background {
+            writeln!(
+                f,
+                "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                x,
+                y,
+                w,
+                h,
+                background.to_hex()
+            )?;
+        }
+        if self.flip_y {
+            writeln!(f, "  <g transform=\"scale(1,-1)\">")?;
+        }
+        let indent = if self.flip_y { "    " } else { "  " };
+        for drawable in self.drawables.iter() {
+            writeln!(f, "{}<g>", indent)?;
+            for path in drawable.to_svg_paths(self.precision).iter() {
+                writeln!(f, "{}  {}", indent, path.to_element())?;
+            }
+            writeln!(f, "{}</g>", indent)?;
+        }
+        if self.flip_y {
+            writeln!(f, "  </g>")?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ops_map_to_path_commands() {
+        let ops = [
+            Op::Move(pt2(1.0, 2.0)),
+            Op::Line(pt2(3.0, 4.0)),
+            Op::Cubic(pt2(5.0, 6.0), pt2(7.0, 8.0), pt2(9.0, 10.0)),
+        ];
+        assert_eq!(ops_to_path(&ops, None), "M1 2 L3 4 C5 6,7 8,9 10");
+    }
+
+    #[test]
+    fn numbers_are_trimmed_to_precision() {
+        assert_eq!(format_number(1.23456, Some(2)), "1.23");
+        assert_eq!(format_number(1.5, Some(3)), "1.5");
+        assert_eq!(format_number(2.0, Some(2)), "2");
+        assert_eq!(format_number(0.125, None), "0.125");
+        let ops = [Op::Move(pt2(1.0 / 3.0, 10.0))];
+        assert_eq!(ops_to_path(&ops, Some(1)), "M0.3 10");
+    }
+
+    #[test]
+    fn negative_zero_is_written_as_zero() {
+        assert_eq!(format_number(-0.0, None), "0");
+        assert_eq!(format_number(-0.0001, Some(2)), "0");
+        assert_eq!(ops_to_path(&[Op::Line(pt2(-0.0, -0.004))], Some(2)), "L0 0");
+    }
+
+    #[test]
+    fn from_rect_covers_the_window() {
+        let document = SvgDocument::from_rect(Rect::from_x_y_w_h(0.0, 0.0, 200.0, 100.0));
+        let svg = document.to_string();
+        assert!(svg.contains("viewBox=\"-100 -50 200 100\""), "{}", svg);
+        assert!(svg.contains("width=\"200\" height=\"100\""), "{}", svg);
+    }
+
+    #[test]
+    fn from_rect_puts_positive_y_at_the_top() {
+        let options = Options {
+            roughness: 0.0,
+            disable_multi_stroke: true,
+            ..Options::default()
+        };
+        let line = crate::generator::line(0.0, 40.0, 10.0, 40.0, options).unwrap();
+        let mut document = SvgDocument::from_rect(Rect::from_x_y_w_h(0.0, 0.0, 200.0, 100.0));
+        document.add(line);
+        let svg = document.to_string();
+        // The path keeps nannou's y = 40 and the group flips it to SVG's y = -40,
+        // which is in the top half of the -50..50 viewBox.
+        assert!(svg.contains("<g transform=\"scale(1,-1)\">"), "{}", svg);
+        assert!(svg.contains("d=\"M0 40 "), "{}", svg);
+        let [_, top, _, height] = document.view_box;
+        assert!(-40.0 > top && -40.0 < top + height / 2.0);
+    }
+}
@@ -0,0 +1,132 @@
+use nannou::glam::{Affine2, Mat2};
+use nannou::prelude::*;
+
+use crate::core::{Drawable, Op};
+
+impl Drawable {
+    /// Applies `affine` to every point of every op, control points included. The
+    /// randomness is kept, and stroke widths stay as they are; see
+    /// `transform_with_strokes` to scale them too.
+    pub fn transform(&mut self, affine: &Affine2) -> &mut Self {
+        for set in self.sets.iter_mut() {
+            for item in set.ops.iter_mut() {
+                let t = |p: Point2| affine.transform_point2(p);
+                *item = match *item {
+                    Op::Move(to) => Op::Move(t(to)),
+                    Op::Line(to) => Op::Line(t(to)),
+                    Op::Cubic(c1, c2, to) => Op::Cubic(t(c1), t(c2), t(to)),
+                };
+            }
+        }
+        self
+    }
+
+    /// Like `transform`, but also scales `stroke_width` and `fill_weight` by the
+    /// average scale of `affine`.
+    pub fn transform_with_strokes(&mut self, affine: &Affine2) -> &mut Self {
+        let scale = affine.matrix2.determinant().abs().sqrt();
+        self.options.stroke_width *= scale;
+        self.options.fill_weight *= scale;
+        self.transform(affine)
+    }
+
+    pub fn translate(&mut self, x: f32, y: f32) -> &mut Self {
+        self.transform(&Affine2::from_translation(vec2(x, y)))
+    }
+
+    /// Rotates counter-clockwise by `radians` around the origin.
+    pub fn rotate(&mut self, radians: f32) -> &mut Self {
+        self.transform(&Affine2::from_angle(radians))
+    }
+
+    /// Scales around the origin. Stroke widths are not scaled.
+    pub fn scale(&mut self, x: f32, y: f32) -> &mut Self {
+        self.transform(&Affine2::from_scale(vec2(x, y)))
+    }
+
+    /// Shears by `x_radians` along the x axis and `y_radians` along the y axis.
+    pub fn skew(&mut self, x_radians: f32, y_radians: f32) -> &mut Self {
+        let matrix = Mat2::from_cols(vec2(1.0, y_radians.tan()), vec2(x_radians.tan(), 1.0));
+        self.transform(&Affine2::from_mat2(matrix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{OpSet, OpSetType, Options};
+
+    fn drawable() -> Drawable {
+        let ops = vec![
+            Op::Move(pt2(1.0, 0.0)),
+            Op::Line(pt2(2.0, 1.0)),
+            Op::Cubic(pt2(0.0, 1.0), pt2(-1.0, 2.0), pt2(3.0, -2.0)),
+        ];
+        let set = OpSet {
+            ops_type: OpSetType::Path,
+            ops,
+        };
+        return Drawable::new("test", Options::default(), vec![set]);
+    }
+
+    fn points(drawable: &Drawable) -> Vec<Point2> {
+        let mut points = vec![];
+        for item in drawable.sets[0].ops.iter() {
+            match *item {
+                Op::Move(to) | Op::Line(to) => points.push(to),
+                Op::Cubic(c1, c2, to) => points.extend([c1, c2, to]),
+            }
+        }
+        return points;
+    }
+
+    fn assert_mapped(transformed: &Drawable, f: impl Fn(Point2) -> Point2) {
+        let expected = Vec::from_iter(points(&drawable()).into_iter().map(f));
+        assert_eq!(points(transformed).len(), expected.len());
+        for (found, expected) in points(transformed).iter().zip(expected.iter()) {
+            assert!(
+                found.distance(*expected) < 1e-5,
+                "{:?} != {:?}",
+                found,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn translate_moves_every_point() {
+        let mut drawable = drawable();
+        drawable.translate(10.0, -5.0);
+        assert_mapped(&drawable, |p| p + vec2(10.0, -5.0));
+    }
+
+    #[test]
+    fn rotate_turns_every_point() {
+        let mut drawable = drawable();
+        drawable.rotate(PI / 2.0);
+        assert_mapped(&drawable, |p| pt2(-p.y, p.x));
+    }
+
+    #[test]
+    fn skew_shears_every_point() {
+        let mut drawable = drawable();
+        drawable.skew(PI / 4.0, 0.0);
+        assert_mapped(&drawable, |p| pt2(p.x + p.y, p.y));
+    }
+
+    #[test]
+    fn only_transform_with_strokes_scales_weights() {
+        let mut drawable = drawable();
+        let (stroke_width, fill_weight) =
+            (drawable.options.stroke_width, drawable.options.fill_weight);
+        drawable.transform(&Affine2::from_scale(vec2(2.0, 2.0)));
+        assert_eq!(drawable.options.stroke_width, stroke_width);
+        assert_eq!(drawable.options.fill_weight, fill_weight);
+
+        let mut scaled = self::drawable();
+        scaled.transform_with_strokes(&Affine2::from_scale(vec2(2.0, 8.0)));
+        assert_eq!(scaled.options.stroke_width, stroke_width * 4.0);
+        assert_eq!(scaled.options.fill_weight, fill_weight * 4.0);
+        assert_mapped(&scaled, |p| pt2(p.x * 2.0, p.y * 8.0));
+    }
+}